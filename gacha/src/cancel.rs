@@ -0,0 +1,111 @@
+use std::{
+    hash::{Hash, Hasher},
+    sync::{Arc, Condvar, Mutex},
+    task::Waker,
+    time::Duration,
+};
+
+/// 取消正在进行的导出，克隆后的令牌共享同一状态
+///
+/// 每页请求前与两页之间的等待中检查，取消后当前请求完成即停止
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<(Mutex<State>, Condvar)>);
+
+#[derive(Debug, Default)]
+struct State {
+    cancelled: bool,
+    /// 正在异步等待的任务，取消时唤醒
+    wakers: Vec<Waker>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 取消导出，正在等待的请求会立即停止等待
+    pub fn cancel(&self) {
+        let (state, condvar) = &*self.0;
+        let wakers = {
+            let mut state = state.lock().unwrap();
+            state.cancelled = true;
+            std::mem::take(&mut state.wakers)
+        };
+        wakers.into_iter().for_each(Waker::wake);
+        condvar.notify_all();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0 .0.lock().unwrap().cancelled
+    }
+
+    /// 等待 `duration` 或直至取消，返回是否已取消
+    pub(crate) fn sleep(&self, duration: Duration) -> bool {
+        let (state, condvar) = &*self.0;
+        let guard = condvar
+            .wait_timeout_while(state.lock().unwrap(), duration, |state| !state.cancelled)
+            .unwrap()
+            .0;
+        guard.cancelled
+    }
+
+    /// 已取消时返回 `true`，否则在取消时唤醒 `waker`
+    #[cfg(feature = "async")]
+    fn register(&self, waker: &Waker) -> bool {
+        let mut state = self.0 .0.lock().unwrap();
+        if state.cancelled {
+            return true;
+        }
+        if !state.wakers.iter().any(|w| w.will_wake(waker)) {
+            state.wakers.push(waker.clone());
+        }
+        false
+    }
+
+    /// [`CancellationToken::sleep`] 的异步版本
+    #[cfg(feature = "async")]
+    pub(crate) async fn sleep_async(&self, duration: Duration) -> bool {
+        CancellableDelay {
+            token: self,
+            delay: futures_timer::Delay::new(duration),
+        }
+        .await
+    }
+}
+
+/// 等待结束时返回 `false`，先被取消时返回 `true`
+#[cfg(feature = "async")]
+struct CancellableDelay<'a> {
+    token: &'a CancellationToken,
+    delay: futures_timer::Delay,
+}
+
+#[cfg(feature = "async")]
+impl std::future::Future for CancellableDelay<'_> {
+    type Output = bool;
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<bool> {
+        if self.token.register(cx.waker()) {
+            return std::task::Poll::Ready(true);
+        }
+        std::pin::Pin::new(&mut self.delay).poll(cx).map(|()| false)
+    }
+}
+
+/// 指向同一状态时相等
+impl PartialEq for CancellationToken {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CancellationToken {}
+
+impl Hash for CancellationToken {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).hash(state)
+    }
+}
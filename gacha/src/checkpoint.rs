@@ -0,0 +1,228 @@
+//! 断点续传，将导出进度保存到文件中，中断后可使用新的链接继续导出
+
+use crate::{
+    gacha_log::{gacha_log_url, pools, record_count, uigf_from_gacha_log, Fetched},
+    req::{fetch_pages, unsync, Pager, RateLimiter, ResponseData},
+    Cancelled, FetchOptions, GachaType, GameType, ProgressEvent, Transport,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    error::Error,
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+};
+use uigf::{Info, UigfV4};
+use url::Url;
+
+/// 导出进度，不含链接与 authkey
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub game_type: GameType,
+    pub pools: Vec<PoolCheckpoint>,
+}
+
+/// 单个卡池的导出进度
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub struct PoolCheckpoint {
+    pub gacha_type: GachaType,
+    /// 是否已取完
+    pub finished: bool,
+    /// 已获取的记录
+    pub data: Option<ResponseData>,
+    /// 下一页的 `end_id`
+    pub end_id: Option<String>,
+}
+
+impl Checkpoint {
+    /// 尚未开始导出的进度
+    pub fn new(game_type: GameType) -> Self {
+        Self {
+            game_type,
+            pools: pools(game_type)
+                .into_iter()
+                .map(|gacha_type| PoolCheckpoint {
+                    gacha_type,
+                    finished: false,
+                    data: None,
+                    end_id: None,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    /// 先写入在文件名后加上 `.tmp` 的临时文件再替换，避免写入中途中断导致文件损坏
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Box<dyn Error>> {
+        let path = path.as_ref();
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        let temp = PathBuf::from(temp);
+        fs::write(&temp, serde_json::to_string(self)?)?;
+        fs::rename(temp, path)?;
+        Ok(())
+    }
+
+    /// 所有卡池均已取完
+    pub fn is_finished(&self) -> bool {
+        self.pools.iter().all(|pool| pool.finished)
+    }
+
+    /// 已获取的记录所属的 UID
+    pub fn uid(&self) -> Option<&str> {
+        self.pools
+            .iter()
+            .find_map(|pool| pool.data.as_ref()?.list.first())
+            .map(|record| record.uid.as_str())
+    }
+}
+
+/// 获取所有卡池，每取得一页便将进度保存到 `path`，成功后删除该文件
+///
+/// `path` 已存在时返回错误，不会覆盖已有的进度；
+/// 中断或取消后可使用 [`resume_uigf_with_url_all`] 继续导出；此方式逐个卡池获取，忽略 `options` 中的并发数
+pub fn get_uigf_with_url_all_with_checkpoint<T: Transport + ?Sized>(
+    transport: &T,
+    game_type: GameType,
+    url: Url,
+    options: &FetchOptions,
+    path: impl AsRef<Path>,
+) -> Result<UigfV4, Box<dyn Error>> {
+    let path = path.as_ref();
+    File::create_new(path).map_err(|error| match error.kind() {
+        io::ErrorKind::AlreadyExists => format!(
+            "A checkpoint already exists at {}, resume it or remove it first",
+            path.display()
+        )
+        .into(),
+        _ => Box::<dyn Error>::from(error),
+    })?;
+    let checkpoint = Checkpoint::new(game_type);
+    checkpoint.save(path)?;
+    run(transport, checkpoint, url, options, path)
+}
+
+/// 从 `path` 中保存的进度继续导出，`url` 可以是重新获取的链接或镜像链接，但不能明确属于其他游戏或服务器
+pub fn resume_uigf_with_url_all<T: Transport + ?Sized>(
+    transport: &T,
+    url: Url,
+    options: &FetchOptions,
+    path: impl AsRef<Path>,
+) -> Result<UigfV4, Box<dyn Error>> {
+    resume(transport, &url.clone(), url, options, path.as_ref())
+}
+
+/// 根据 `detect_url` 检查游戏，使用 `url` 请求，两者在客户端替换了接口地址时不同
+///
+/// 只在链接明确属于其他游戏或服务器时拒绝，无法判断游戏的镜像或自定义链接照常继续
+pub(crate) fn resume<T: Transport + ?Sized>(
+    transport: &T,
+    detect_url: &Url,
+    url: Url,
+    options: &FetchOptions,
+    path: &Path,
+) -> Result<UigfV4, Box<dyn Error>> {
+    let checkpoint = Checkpoint::load(path)?;
+    if !GameType::candidates_from_url(detect_url)?.contains(&checkpoint.game_type) {
+        return Err(format!(
+            "The URL is not for {:?}, which the checkpoint is for",
+            checkpoint.game_type
+        )
+        .into());
+    }
+    run(transport, checkpoint, url, options, path)
+}
+
+fn run<T: Transport + ?Sized>(
+    transport: &T,
+    mut checkpoint: Checkpoint,
+    url: Url,
+    options: &FetchOptions,
+    path: &Path,
+) -> Result<UigfV4, Box<dyn Error>> {
+    let limiter = RateLimiter::new(options.request_interval);
+
+    let mut cancelled = false;
+    for index in 0..checkpoint.pools.len() {
+        let pool = &checkpoint.pools[index];
+        if pool.finished {
+            continue;
+        }
+        let gacha_type = pool.gacha_type;
+        options.report(ProgressEvent::PoolStarted { gacha_type });
+        let mut pager = Pager::resume(
+            gacha_log_url(pool.gacha_type, url.clone(), options),
+            pool.data.clone(),
+            pool.end_id.clone(),
+        )
+        .map_err(unsync)?;
+
+        let result = fetch_pages(
+            transport,
+            gacha_type,
+            &mut pager,
+            &limiter,
+            options,
+            |pager| {
+                let last = pager.data().and_then(|data| data.list.last());
+                if let (Some(expected), Some(record)) = (checkpoint.uid(), last) {
+                    if expected != record.uid {
+                        return Err(
+                            "The URL belongs to a different account than the checkpoint".into()
+                        );
+                    }
+                }
+                let pool = &mut checkpoint.pools[index];
+                pool.data = pager.data().cloned();
+                pool.end_id = pager.end_id().map(str::to_string);
+                pool.finished = pager.is_finished();
+                checkpoint.save(path)
+            },
+        );
+        if let Err(error) = result {
+            if error.is::<Cancelled>() {
+                cancelled = true;
+                break;
+            }
+            return Err(error);
+        }
+
+        let data = pager.finish().map_err(unsync)?;
+        options.report(ProgressEvent::PoolFinished {
+            gacha_type,
+            count: data.list.len(),
+        });
+        let pool = &mut checkpoint.pools[index];
+        pool.data = Some(data);
+        pool.end_id = None;
+        pool.finished = true;
+        checkpoint.save(path)?;
+    }
+
+    let game_type = checkpoint.game_type;
+    let uigfs = checkpoint
+        .pools
+        .into_iter()
+        .filter_map(|pool| {
+            Some(uigf_from_gacha_log(
+                pool.gacha_type,
+                vec![pool.data?],
+                Info::new(),
+            ))
+        })
+        .collect();
+    let uigf = Fetched {
+        uigfs,
+        cancelled,
+        ..Fetched::default()
+    }
+    .merge(game_type, options.export_info())?;
+    fs::remove_file(path)?;
+    options.report(ProgressEvent::ExportFinished {
+        count: record_count(&uigf),
+    });
+    Ok(uigf)
+}
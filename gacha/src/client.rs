@@ -0,0 +1,352 @@
+//! 统一配置请求方式、获取选项与链接的客户端
+
+#[cfg(feature = "http")]
+use crate::MinreqTransport;
+use crate::{
+    checkpoint::resume, gacha_url::find_gacha_url, get_account_with_url_incremental_with_options,
+    get_account_with_url_since_with_options, get_gacha_log_with_url_with_options,
+    get_uigf_with_url_all_with_checkpoint, get_uigf_with_url_all_with_options,
+    get_uigf_with_url_with_options, FetchOptions, GachaType, GameType, ResponseData, Transport,
+};
+#[cfg(feature = "async")]
+use crate::{
+    get_gacha_log_with_url_with_options_async, get_uigf_with_url_all_with_options_async,
+    get_uigf_with_url_with_options_async, AsyncTransport,
+};
+use std::{collections::HashMap, error::Error, path::Path, time::Duration};
+use uigf::{GachaAccount, GachaItem, InfoBuilder, UigfV4};
+use url::Url;
+
+/// 获取抽卡记录的客户端，使用 [`GachaClientBuilder`] 创建
+#[derive(Debug, Clone)]
+pub struct GachaClient<T> {
+    transport: T,
+    options: FetchOptions,
+    base_url: Option<Url>,
+    lang: Option<String>,
+}
+
+#[cfg(feature = "http")]
+impl Default for GachaClient<MinreqTransport> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "http")]
+impl GachaClient<MinreqTransport> {
+    /// 使用默认配置的客户端
+    pub fn new() -> Self {
+        Self::builder().build()
+    }
+
+    pub fn builder() -> GachaClientBuilder<MinreqTransport> {
+        GachaClientBuilder::new(MinreqTransport::new())
+    }
+}
+
+impl<T> GachaClient<T> {
+    /// 使用指定的请求方式
+    pub fn builder_with_transport(transport: T) -> GachaClientBuilder<T> {
+        GachaClientBuilder::new(transport)
+    }
+
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    pub fn options(&self) -> &FetchOptions {
+        &self.options
+    }
+
+    /// 替换链接中的接口地址与语言
+    pub fn url(&self, url: Url) -> Url {
+        let mut url = match &self.base_url {
+            Some(base_url) => {
+                let mut base_url = base_url.clone();
+                base_url.set_query(url.query());
+                base_url
+            }
+            None => url,
+        };
+        if let Some(lang) = &self.lang {
+            let query = url
+                .query_pairs()
+                .filter(|(key, _)| key != "lang")
+                .fold(
+                    url::form_urlencoded::Serializer::new(String::new()),
+                    |mut serializer, (key, value)| {
+                        serializer.append_pair(&key, &value);
+                        serializer
+                    },
+                )
+                .append_pair("lang", lang)
+                .finish();
+            url.set_query(Some(&query));
+        }
+        url
+    }
+}
+
+impl<T: Transport> GachaClient<T> {
+    /// 见 [`get_uigf_with_url_all_with_options`]
+    pub fn get_uigf_all(&self, game_type: GameType, url: Url) -> Result<UigfV4, Box<dyn Error>>
+    where
+        T: Sync,
+    {
+        get_uigf_with_url_all_with_options(&self.transport, game_type, self.url(url), &self.options)
+    }
+
+    /// 根据替换前的链接判断游戏，见 [`GameType::from_url`]
+    pub fn get_uigf_all_detected(&self, url: Url) -> Result<UigfV4, Box<dyn Error>>
+    where
+        T: Sync,
+    {
+        self.get_uigf_all(GameType::from_url(&url)?, url)
+    }
+
+    /// 见 [`get_uigf_with_url_with_options`]
+    pub fn get_uigf(&self, gacha_type: GachaType, url: Url) -> Result<UigfV4, Box<dyn Error>> {
+        get_uigf_with_url_with_options(&self.transport, gacha_type, self.url(url), &self.options)
+    }
+
+    /// 见 [`get_gacha_log_with_url_with_options`]
+    pub fn get_gacha_log(
+        &self,
+        gacha_type: GachaType,
+        url: Url,
+    ) -> Result<ResponseData, Box<dyn Error>> {
+        get_gacha_log_with_url_with_options(
+            &self.transport,
+            gacha_type,
+            self.url(url),
+            &self.options,
+        )
+    }
+
+    /// 见 [`get_uigf_with_url_all_with_checkpoint`]
+    pub fn get_uigf_all_with_checkpoint(
+        &self,
+        game_type: GameType,
+        url: Url,
+        path: impl AsRef<Path>,
+    ) -> Result<UigfV4, Box<dyn Error>> {
+        get_uigf_with_url_all_with_checkpoint(
+            &self.transport,
+            game_type,
+            self.url(url),
+            &self.options,
+            path,
+        )
+    }
+
+    /// 见 [`resume_uigf_with_url_all`](crate::resume_uigf_with_url_all)，根据替换前的链接判断游戏
+    pub fn resume_uigf_all(
+        &self,
+        url: Url,
+        path: impl AsRef<Path>,
+    ) -> Result<UigfV4, Box<dyn Error>> {
+        resume(
+            &self.transport,
+            &url,
+            self.url(url.clone()),
+            &self.options,
+            path.as_ref(),
+        )
+    }
+
+    /// 见 [`get_account_with_url_incremental_with_options`]
+    pub fn get_account_incremental<A>(&self, account: &A, url: Url) -> Result<A, Box<dyn Error>>
+    where
+        A: GachaAccount,
+        <A::Item as GachaItem>::GachaType: Into<GachaType>,
+        T: Sync,
+    {
+        get_account_with_url_incremental_with_options(
+            &self.transport,
+            account,
+            self.url(url),
+            &self.options,
+        )
+    }
+
+    /// 见 [`get_account_with_url_since_with_options`]
+    pub fn get_account_since<A>(
+        &self,
+        since: &HashMap<GachaType, String>,
+        url: Url,
+    ) -> Result<Option<A>, Box<dyn Error>>
+    where
+        A: GachaAccount,
+        <A::Item as GachaItem>::GachaType: Into<GachaType>,
+        T: Sync,
+    {
+        get_account_with_url_since_with_options(
+            &self.transport,
+            since,
+            self.url(url),
+            &self.options,
+        )
+    }
+
+    /// 见 [`get_gacha_url_with_game_data_path_with_transport`](crate::get_gacha_url_with_game_data_path_with_transport)，只保留 [`FetchOptions::query_keys`] 中的查询参数
+    pub fn get_gacha_url_with_game_data_path(
+        &self,
+        game_data_path: impl AsRef<Path>,
+    ) -> Result<Url, Box<dyn Error>> {
+        find_gacha_url(&self.transport, game_data_path, &self.options.query_keys)
+    }
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncTransport + Sync> GachaClient<T> {
+    /// 见 [`get_uigf_with_url_all_with_options_async`]
+    pub async fn get_uigf_all_async(
+        &self,
+        game_type: GameType,
+        url: Url,
+    ) -> Result<UigfV4, Box<dyn Error + Send + Sync>> {
+        get_uigf_with_url_all_with_options_async(
+            &self.transport,
+            game_type,
+            self.url(url),
+            &self.options,
+        )
+        .await
+    }
+
+    /// 见 [`get_uigf_with_url_with_options_async`]
+    pub async fn get_uigf_async(
+        &self,
+        gacha_type: GachaType,
+        url: Url,
+    ) -> Result<UigfV4, Box<dyn Error + Send + Sync>> {
+        get_uigf_with_url_with_options_async(
+            &self.transport,
+            gacha_type,
+            self.url(url),
+            &self.options,
+        )
+        .await
+    }
+
+    /// 见 [`get_gacha_log_with_url_with_options_async`]
+    pub async fn get_gacha_log_async(
+        &self,
+        gacha_type: GachaType,
+        url: Url,
+    ) -> Result<ResponseData, Box<dyn Error + Send + Sync>> {
+        get_gacha_log_with_url_with_options_async(
+            &self.transport,
+            gacha_type,
+            self.url(url),
+            &self.options,
+        )
+        .await
+    }
+}
+
+/// [`GachaClient`] 的构建器
+#[derive(Debug, Clone)]
+pub struct GachaClientBuilder<T> {
+    transport: T,
+    options: FetchOptions,
+    base_url: Option<Url>,
+    lang: Option<String>,
+}
+
+impl<T> GachaClientBuilder<T> {
+    pub fn new(transport: T) -> Self {
+        Self {
+            transport,
+            options: FetchOptions::new(),
+            base_url: None,
+            lang: None,
+        }
+    }
+
+    /// 替换请求方式，已设置的选项保持不变
+    pub fn transport<U>(self, transport: U) -> GachaClientBuilder<U> {
+        GachaClientBuilder {
+            transport,
+            options: self.options,
+            base_url: self.base_url,
+            lang: self.lang,
+        }
+    }
+
+    /// 替换全部获取选项，包括已设置的每页记录数与翻页间隔
+    pub fn options(mut self, options: FetchOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// 见 [`FetchOptions::page_size`]
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.options = self.options.page_size(page_size);
+        self
+    }
+
+    /// 见 [`FetchOptions::query_keys`]
+    pub fn query_keys<I, S>(mut self, query_keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.options = self.options.query_keys(query_keys);
+        self
+    }
+
+    /// 见 [`FetchOptions::page_interval`]
+    pub fn page_interval(mut self, page_interval: Duration) -> Self {
+        self.options = self.options.page_interval(page_interval);
+        self
+    }
+
+    /// 见 [`FetchOptions::info`]
+    pub fn info(mut self, info: InfoBuilder) -> Self {
+        self.options = self.options.info(info);
+        self
+    }
+
+    /// 替换链接中查询参数以外的部分，如使用镜像或本地测试服务器
+    pub fn base_url(mut self, base_url: Url) -> Self {
+        self.base_url = Some(base_url);
+        self
+    }
+
+    /// 替换链接中的 `lang`，即返回的物品名称的语言
+    pub fn lang(mut self, lang: impl Into<String>) -> Self {
+        self.lang = Some(lang.into());
+        self
+    }
+
+    pub fn build(self) -> GachaClient<T> {
+        GachaClient {
+            transport: self.transport,
+            options: self.options,
+            base_url: self.base_url,
+            lang: self.lang,
+        }
+    }
+}
+
+#[cfg(feature = "http")]
+impl GachaClientBuilder<MinreqTransport> {
+    /// 见 [`MinreqTransport::timeout`]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.transport = self.transport.timeout(timeout);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.transport = self.transport.user_agent(user_agent);
+        self
+    }
+
+    /// 见 [`MinreqTransport::proxy`]
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.transport = self.transport.proxy(proxy);
+        self
+    }
+}
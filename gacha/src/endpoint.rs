@@ -0,0 +1,271 @@
+//! 抽卡记录接口的地址，仅凭 authkey 拼接抽卡链接，以及根据链接判断游戏与服务器
+
+use crate::GameType;
+use std::error::Error;
+use uigf::Region;
+use url::{form_urlencoded, Url};
+
+/// 抽卡记录接口
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Endpoint {
+    /// 接口地址，不含查询参数
+    pub url: &'static str,
+    pub game_biz: &'static str,
+    /// 未指定语言时使用的 `lang`
+    pub lang: &'static str,
+}
+
+impl Endpoint {
+    /// 各游戏与服务器的接口，接口地址变更时只需修改此处
+    pub const fn of(game_type: GameType) -> Self {
+        let (url, game_biz, lang) = match game_type {
+            GameType::Hk4eCN => (
+                "https://public-operation-hk4e.mihoyo.com/gacha_info/api/getGachaLog",
+                "hk4e_cn",
+                "zh-cn",
+            ),
+            GameType::Hk4eGlobal => (
+                "https://public-operation-hk4e-sg.hoyoverse.com/gacha_info/api/getGachaLog",
+                "hk4e_global",
+                "en-us",
+            ),
+            GameType::HkrpgCN => (
+                "https://public-operation-hkrpg.mihoyo.com/common/gacha_record/api/getGachaLog",
+                "hkrpg_cn",
+                "zh-cn",
+            ),
+            GameType::HkrpgGlobal => (
+                "https://public-operation-hkrpg-sg.hoyoverse.com/common/gacha_record/api/getGachaLog",
+                "hkrpg_global",
+                "en-us",
+            ),
+            GameType::NapCN => (
+                "https://public-operation-nap.mihoyo.com/common/gacha_record/api/getGachaLog",
+                "nap_cn",
+                "zh-cn",
+            ),
+            GameType::NapGlobal => (
+                "https://public-operation-nap-sg.hoyoverse.com/common/gacha_record/api/getGachaLog",
+                "nap_global",
+                "en-us",
+            ),
+        };
+        Self {
+            url,
+            game_biz,
+            lang,
+        }
+    }
+}
+
+/// 已知的游戏与服务器
+const GAME_TYPES: [GameType; 6] = [
+    GameType::Hk4eCN,
+    GameType::Hk4eGlobal,
+    GameType::HkrpgCN,
+    GameType::HkrpgGlobal,
+    GameType::NapCN,
+    GameType::NapGlobal,
+];
+
+impl GameType {
+    /// 根据抽卡链接的域名、路径与 `game_biz` 判断游戏与服务器
+    ///
+    /// 见 [`GameType::candidates_from_url`]，无法确定唯一的游戏或各项互相矛盾时返回错误
+    pub fn from_url(url: &Url) -> Result<Self, Box<dyn Error>> {
+        match GameType::candidates_from_url(url)?[..] {
+            [game_type] => Ok(game_type),
+            [] => Err("The host, path and game_biz of the URL contradict each other".into()),
+            _ => Err("Cannot determine the game from the URL".into()),
+        }
+    }
+
+    /// 抽卡链接可能属于的游戏与服务器
+    ///
+    /// 域名、路径与 `game_biz` 每项只排除与之不符的游戏，镜像或自定义的链接可能得到全部游戏；
+    /// 各项互相矛盾时为空，`game_biz` 未知时返回错误
+    pub fn candidates_from_url(url: &Url) -> Result<Vec<Self>, Box<dyn Error>> {
+        let host = url.host_str().unwrap_or_default();
+        let labels = host.split(['.', '-']).collect::<Vec<_>>();
+        let segments = url.path().split('/').collect::<Vec<_>>();
+        let game_biz = url
+            .query_pairs()
+            .find(|(key, _)| key == "game_biz")
+            .map(|(_, value)| value.into_owned());
+        if let Some(game_biz) = &game_biz {
+            if !GAME_TYPES
+                .iter()
+                .any(|game_type| Endpoint::of(*game_type).game_biz == game_biz)
+            {
+                return Err(format!("Unknown game_biz: {game_biz}").into());
+            }
+        }
+
+        let endpoints = GAME_TYPES.map(|game_type| {
+            let endpoint = Endpoint::of(game_type);
+            let endpoint_url = Url::parse(endpoint.url).expect("Invalid endpoint URL");
+            (game_type, endpoint, endpoint_url)
+        });
+        let game = |endpoint: &Endpoint| endpoint.game_biz.split('_').next().unwrap_or_default();
+        let domain = |endpoint_url: &Url| {
+            let host = endpoint_url.host_str().unwrap_or_default();
+            host.rmatch_indices('.')
+                .nth(1)
+                .map_or(host, |(index, _)| &host[index + 1..])
+                .to_string()
+        };
+        let is_global = |endpoint: &Endpoint| endpoint.game_biz.ends_with("_global");
+        let host_has_game = endpoints
+            .iter()
+            .any(|(_, endpoint, _)| labels.contains(&game(endpoint)));
+        // 早期的国际服接口如 `api-os-takumi.mihoyo.com` 使用国服的域名，以 `os` 区分
+        let host_is_global = if labels.contains(&"os") {
+            Some(true)
+        } else {
+            endpoints
+                .iter()
+                .find(|(_, _, endpoint_url)| host.ends_with(&domain(endpoint_url)))
+                .map(|(_, endpoint, _)| is_global(endpoint))
+        };
+        let path_has_game = endpoints
+            .iter()
+            .any(|(_, endpoint, _)| segments.contains(&game(endpoint)));
+        let path_is_known = endpoints
+            .iter()
+            .any(|(_, _, endpoint_url)| url.path() == endpoint_url.path());
+
+        Ok(endpoints
+            .iter()
+            .filter(|(_, endpoint, endpoint_url)| {
+                (!host_has_game || labels.contains(&game(endpoint)))
+                    && host_is_global.is_none_or(|global| global == is_global(endpoint))
+                    && (!path_has_game || segments.contains(&game(endpoint)))
+                    && (!path_is_known || url.path() == endpoint_url.path())
+                    && game_biz
+                        .as_ref()
+                        .is_none_or(|game_biz| endpoint.game_biz == game_biz)
+            })
+            .map(|(game_type, _, _)| *game_type)
+            .collect())
+    }
+
+    /// 根据抽卡链接判断服务器，先由 [`GameType::from_url`] 判断游戏及国服或国际服，再查找链接中的 `region`
+    ///
+    /// 游戏内打开的链接含有 `region`；不含时只有该接口仅服务一个服务器才能确定，否则返回 `None`；
+    /// `region` 不属于该接口时返回错误
+    pub fn region_from_url(url: &Url) -> Result<Option<Region>, Box<dyn Error>> {
+        let game_type = GameType::from_url(url)?;
+        let regions = game_type.regions();
+        let name = url
+            .query_pairs()
+            .find(|(key, _)| key == "region")
+            .map(|(_, value)| value.into_owned());
+        let Some(name) = name else {
+            return Ok(match regions[..] {
+                [region] => Some(region),
+                _ => None,
+            });
+        };
+        regions
+            .into_iter()
+            .find(|region| region.name == name)
+            .map(Some)
+            .ok_or_else(|| format!("Unknown region for {game_type:?}: {name}").into())
+    }
+}
+
+/// 使用 authkey 拼接抽卡链接
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct GachaUrlBuilder {
+    game_type: GameType,
+    authkey: String,
+    authkey_ver: String,
+    sign_type: String,
+    lang: Option<String>,
+}
+
+impl GachaUrlBuilder {
+    /// `authkey` 可以是 authkey 本身、经过 URL 编码的 authkey，或是含有 authkey 的完整或不完整的链接；
+    /// 链接中的 `authkey_ver`、`sign_type` 与 `lang` 会一并使用
+    pub fn new(game_type: GameType, authkey: impl AsRef<str>) -> Self {
+        let mut builder = Self {
+            game_type,
+            authkey: String::new(),
+            authkey_ver: "1".to_string(),
+            sign_type: "2".to_string(),
+            lang: None,
+        };
+        let authkey = authkey.as_ref().trim();
+        let is_url = ["authkey=", "?", "://"]
+            .iter()
+            .any(|pattern| authkey.contains(pattern));
+        if !is_url {
+            builder.authkey = decode_authkey(authkey);
+            return builder;
+        }
+
+        let query = authkey.split('#').next().unwrap_or_default();
+        let query = query.split_once('?').map_or(query, |(_, query)| query);
+        for (key, value) in form_urlencoded::parse(query.replace('+', "%2B").as_bytes()) {
+            match key.as_ref() {
+                "authkey" => builder.authkey = value.into_owned(),
+                "authkey_ver" => builder.authkey_ver = value.into_owned(),
+                "sign_type" => builder.sign_type = value.into_owned(),
+                "lang" => builder.lang = Some(value.into_owned()),
+                _ => {}
+            }
+        }
+        builder
+    }
+
+    /// 默认为 1
+    pub fn authkey_ver(mut self, authkey_ver: impl Into<String>) -> Self {
+        self.authkey_ver = authkey_ver.into();
+        self
+    }
+
+    /// 默认为 2
+    pub fn sign_type(mut self, sign_type: impl Into<String>) -> Self {
+        self.sign_type = sign_type.into();
+        self
+    }
+
+    /// 默认国服为 zh-cn，国际服为 en-us
+    pub fn lang(mut self, lang: impl Into<String>) -> Self {
+        self.lang = Some(lang.into());
+        self
+    }
+
+    pub fn build(self) -> Result<Url, Box<dyn Error>> {
+        if self.authkey.is_empty() {
+            return Err("No authkey found".into());
+        }
+        let endpoint = Endpoint::of(self.game_type);
+        let mut url = Url::parse(endpoint.url)?;
+        url.query_pairs_mut()
+            .append_pair("authkey_ver", &self.authkey_ver)
+            .append_pair("sign_type", &self.sign_type)
+            .append_pair("authkey", &self.authkey)
+            .append_pair("lang", self.lang.as_deref().unwrap_or(endpoint.lang))
+            .append_pair("game_biz", endpoint.game_biz);
+        Ok(url)
+    }
+}
+
+/// 使用 authkey 或含有 authkey 的链接拼接抽卡链接，见 [`GachaUrlBuilder::new`]
+pub fn get_gacha_url_with_authkey(
+    game_type: GameType,
+    authkey: impl AsRef<str>,
+) -> Result<Url, Box<dyn Error>> {
+    GachaUrlBuilder::new(game_type, authkey).build()
+}
+
+/// authkey 为 Base64，本身不含 `%`，含有时视为经过 URL 编码
+fn decode_authkey(authkey: &str) -> String {
+    if !authkey.contains('%') {
+        return authkey.to_string();
+    }
+    form_urlencoded::parse(format!("authkey={authkey}").replace('+', "%2B").as_bytes())
+        .next()
+        .map_or_else(String::new, |(_, value)| value.into_owned())
+}
@@ -0,0 +1,35 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+};
+use uigf::UigfV4;
+
+/// 接口返回的错误，如 retcode 为 -110 时表示请求过于频繁
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ApiError {
+    pub retcode: i64,
+    pub message: String,
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{} (retcode {})", self.message, self.retcode)
+    }
+}
+
+impl Error for ApiError {}
+
+/// 导出被 [`CancellationToken`](crate::CancellationToken) 取消
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct Cancelled {
+    /// 取消前已获取的记录，尚未获取到任何记录时为 `None`
+    pub partial: Option<UigfV4>,
+}
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("The export was cancelled")
+    }
+}
+
+impl Error for Cancelled {}
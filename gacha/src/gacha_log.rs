@@ -1,99 +1,351 @@
+#[cfg(feature = "http")]
+use crate::GachaClient;
 use crate::{
-    get_gacha_url,
-    req::{fetch_gacha_log, filter_url, ResponseData},
-    GachaType, GameType,
+    req::{fetch_gacha_log, filter_url, sendable, unsync, RateLimiter, ResponseData},
+    Cancelled, FetchOptions, GachaType, GameType, ProgressEvent, Transport,
 };
-use std::error::Error;
-use uigf::{hk4e::Hk4e, hkrpg::Hkrpg, nap::Nap, Info, UigfV4};
+use std::{
+    error::Error,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+use uigf::{hk4e::Hk4e, hkrpg::Hkrpg, nap::Nap, GachaAccount, GachaItem, Info, UigfV4};
 use url::Url;
 
+#[cfg(all(feature = "http", feature = "discovery-windows"))]
 pub fn get_uigf_all(game_type: GameType) -> Result<UigfV4, Box<dyn Error>> {
-    get_uigf_with_url_all(game_type, get_gacha_url(game_type)?)
+    get_uigf_with_url_all(game_type, crate::get_gacha_url(game_type)?)
 }
 
+#[cfg(feature = "http")]
 pub fn get_uigf_with_url_all(game_type: GameType, url: Url) -> Result<UigfV4, Box<dyn Error>> {
-    match game_type {
-        GameType::Hk4eCN | GameType::Hk4eGlobal => {
-            let uigf_data = uigf::hk4e::GachaType::all_variants()
-                .iter()
-                .map(|gacha_type| get_uigf_with_url(GachaType::Hk4e(*gacha_type), url.clone()))
-                .filter_map(|uigf_data| uigf_data.ok())
-                .collect::<Vec<_>>();
-            let data = uigf_data
-                .iter()
-                .filter_map(|uigf_data| uigf_data.hk4e.clone())
-                .flatten()
-                .collect::<Vec<_>>();
-            if data.is_empty() {
-                Err("No data found".into())
-            } else {
-                Ok(UigfV4::new_hk4e(vec![Hk4e {
-                    uid: data[0].uid.clone(),
-                    timezone: data[0].timezone,
-                    lang: data[0].lang,
-                    list: data.into_iter().map(|data| data.list).flatten().collect(),
-                }]))
-            }
+    GachaClient::new().get_uigf_all(game_type, url)
+}
+
+/// 根据链接判断游戏并获取所有卡池，见 [`GameType::from_url`]
+#[cfg(feature = "http")]
+pub fn get_uigf_with_url_all_detected(url: Url) -> Result<UigfV4, Box<dyn Error>> {
+    GachaClient::new().get_uigf_all_detected(url)
+}
+
+pub fn get_uigf_with_url_all_with_transport<T: Transport + ?Sized>(
+    transport: &T,
+    game_type: GameType,
+    url: Url,
+) -> Result<UigfV4, Box<dyn Error>> {
+    let options = FetchOptions::new();
+    let limiter = RateLimiter::new(options.request_interval);
+    fetch_pools(transport, &Pool::all(game_type), &url, &limiter, &options)
+        .merge(game_type, options.export_info())
+}
+
+/// 按选项获取所有卡池，同时获取多个卡池时结果与逐个获取相同
+///
+/// 任一卡池获取失败时返回该卡池的错误，同时获取多个卡池时除 [`ApiError`](crate::ApiError) 外的错误只保留错误信息；
+/// 取消时返回 [`Cancelled`]，其中包含已获取的记录
+pub fn get_uigf_with_url_all_with_options<T: Transport + Sync + ?Sized>(
+    transport: &T,
+    game_type: GameType,
+    url: Url,
+    options: &FetchOptions,
+) -> Result<UigfV4, Box<dyn Error>> {
+    let uigf = fetch_pools_with_options(transport, &Pool::all(game_type), &url, options)
+        .merge(game_type, options.export_info())?;
+    options.report(ProgressEvent::ExportFinished {
+        count: record_count(&uigf),
+    });
+    Ok(uigf)
+}
+
+/// 要获取的卡池，`since` 为已知最新的记录 ID，翻页到该记录即停止
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Pool {
+    pub(crate) gacha_type: GachaType,
+    pub(crate) since: Option<String>,
+}
+
+impl Pool {
+    /// 游戏的所有卡池，从头获取
+    pub(crate) fn all(game_type: GameType) -> Vec<Self> {
+        pools(game_type)
+            .into_iter()
+            .map(|gacha_type| Self {
+                gacha_type,
+                since: None,
+            })
+            .collect()
+    }
+}
+
+/// 各卡池的获取结果，`error` 为按卡池顺序第一个获取失败的卡池的错误
+#[derive(Debug, Default)]
+pub(crate) struct Fetched {
+    pub(crate) uigfs: Vec<UigfV4>,
+    pub(crate) cancelled: bool,
+    pub(crate) error: Option<Box<dyn Error>>,
+}
+
+impl Fetched {
+    /// 合并为一个账号，有卡池获取失败时返回该错误，已取消时返回包含已获取记录的 [`Cancelled`]
+    pub(crate) fn merge(self, game_type: GameType, info: Info) -> Result<UigfV4, Box<dyn Error>> {
+        if let Some(error) = self.error {
+            return Err(error);
         }
-        GameType::HkrpgCN | GameType::HkrpgGlobal => {
-            let uigf_data = uigf::hkrpg::GachaType::all_variants()
-                .iter()
-                .map(|gacha_type| get_uigf_with_url(GachaType::Hkrpg(*gacha_type), url.clone()))
-                .filter_map(|uigf_data| uigf_data.ok())
-                .collect::<Vec<_>>();
-            let data = uigf_data
-                .iter()
-                .filter_map(|uigf_data| uigf_data.hkrpg.clone())
-                .flatten()
-                .collect::<Vec<_>>();
-            if data.is_empty() {
-                Err("No data found".into())
-            } else {
-                Ok(UigfV4::new_hkrpg(vec![Hkrpg {
-                    uid: data[0].uid.clone(),
-                    timezone: data[0].timezone,
-                    lang: data[0].lang,
-                    list: data.into_iter().map(|data| data.list).flatten().collect(),
-                }]))
-            }
+        let uigf = merge_pools(game_type, self.uigfs, info);
+        if self.cancelled {
+            return Err(Box::new(Cancelled { partial: uigf.ok() }));
         }
-        GameType::NapCN | GameType::NapGlobal => {
-            let uigf_data = uigf::nap::GachaType::all_variants()
-                .iter()
-                .map(|gacha_type| get_uigf_with_url(GachaType::Nap(*gacha_type), url.clone()))
-                .filter_map(|uigf_data| uigf_data.ok())
-                .collect::<Vec<_>>();
-            let data = uigf_data
-                .iter()
-                .filter_map(|uigf_data| uigf_data.nap.clone())
-                .flatten()
-                .collect::<Vec<_>>();
-            if data.is_empty() {
-                Err("No data found".into())
-            } else {
-                Ok(UigfV4::new_nap(vec![Nap {
-                    uid: data[0].uid.clone(),
-                    timezone: data[0].timezone,
-                    lang: data[0].lang,
-                    list: data.into_iter().map(|data| data.list).flatten().collect(),
-                }]))
+        uigf.map_err(unsync)
+    }
+}
+
+/// 拆分单个卡池的结果为已获取的记录与是否已取消，取消以外的错误原样返回
+fn split_result(
+    result: Result<UigfV4, Box<dyn Error>>,
+) -> Result<(Option<UigfV4>, bool), Box<dyn Error>> {
+    match result {
+        Ok(uigf) => Ok((Some(uigf), false)),
+        Err(error) => error
+            .downcast::<Cancelled>()
+            .map(|cancelled| (cancelled.partial, true)),
+    }
+}
+
+pub(crate) fn fetch_pools_with_options<T: Transport + Sync + ?Sized>(
+    transport: &T,
+    pools: &[Pool],
+    url: &Url,
+    options: &FetchOptions,
+) -> Fetched {
+    let limiter = RateLimiter::new(options.request_interval);
+    if options.concurrency > 1 {
+        fetch_pools_concurrent(transport, pools, url, &limiter, options)
+    } else {
+        fetch_pools(transport, pools, url, &limiter, options)
+    }
+}
+
+/// 逐个获取卡池，获取失败或取消后不再获取之后的卡池
+fn fetch_pools<T: Transport + ?Sized>(
+    transport: &T,
+    pools: &[Pool],
+    url: &Url,
+    limiter: &RateLimiter,
+    options: &FetchOptions,
+) -> Fetched {
+    let mut fetched = Fetched::default();
+    for pool in pools {
+        match split_result(fetch_pool(transport, pool, url.clone(), limiter, options)) {
+            Ok((uigf, cancelled)) => {
+                fetched.uigfs.extend(uigf);
+                if cancelled {
+                    fetched.cancelled = true;
+                    break;
+                }
+            }
+            Err(error) => {
+                fetched.error = Some(error);
+                break;
             }
         }
     }
+    fetched
+}
+
+/// 同时获取多个卡池，结果按卡池顺序排列，获取失败或取消后不再开始获取新的卡池
+fn fetch_pools_concurrent<T: Transport + Sync + ?Sized>(
+    transport: &T,
+    pools: &[Pool],
+    url: &Url,
+    limiter: &RateLimiter,
+    options: &FetchOptions,
+) -> Fetched {
+    let next = AtomicUsize::new(0);
+    let cancelled = AtomicBool::new(false);
+    let failed = AtomicBool::new(false);
+    let results = Mutex::new(vec![None; pools.len()]);
+    let errors = Mutex::new(Vec::new());
+    thread::scope(|scope| {
+        for _ in 0..options.concurrency.min(pools.len()) {
+            scope.spawn(|| loop {
+                if failed.load(Ordering::Relaxed) {
+                    break;
+                }
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(pool) = pools.get(index) else {
+                    break;
+                };
+                match split_result(fetch_pool(transport, pool, url.clone(), limiter, options)) {
+                    Ok((uigf, is_cancelled)) => {
+                        results.lock().unwrap()[index] = uigf;
+                        if is_cancelled {
+                            cancelled.store(true, Ordering::Relaxed);
+                            break;
+                        }
+                    }
+                    Err(error) => {
+                        errors.lock().unwrap().push((index, sendable(error)));
+                        failed.store(true, Ordering::Relaxed);
+                        break;
+                    }
+                }
+            });
+        }
+    });
+    Fetched {
+        uigfs: results
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .collect(),
+        cancelled: cancelled.into_inner(),
+        error: errors
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .min_by_key(|(index, _)| *index)
+            .map(|(_, error)| unsync(error)),
+    }
+}
+
+fn fetch_pool<T: Transport + ?Sized>(
+    transport: &T,
+    pool: &Pool,
+    url: Url,
+    limiter: &RateLimiter,
+    options: &FetchOptions,
+) -> Result<UigfV4, Box<dyn Error>> {
+    let gacha_log = fetch_gacha_log(
+        transport,
+        pool.gacha_type,
+        gacha_log_url(pool.gacha_type, url, options),
+        pool.since.as_deref(),
+        limiter,
+        options,
+    )?;
+    Ok(uigf_from_gacha_log(
+        pool.gacha_type,
+        vec![gacha_log],
+        Info::new(),
+    ))
 }
 
+/// 游戏的所有卡池
+pub(crate) fn pools(game_type: GameType) -> Vec<GachaType> {
+    match game_type {
+        GameType::Hk4eCN | GameType::Hk4eGlobal => pools_of::<Hk4e>(),
+        GameType::HkrpgCN | GameType::HkrpgGlobal => pools_of::<Hkrpg>(),
+        GameType::NapCN | GameType::NapGlobal => pools_of::<Nap>(),
+    }
+}
+
+pub(crate) fn pools_of<A>() -> Vec<GachaType>
+where
+    A: GachaAccount,
+    <A::Item as GachaItem>::GachaType: Into<GachaType>,
+{
+    A::Item::gacha_types()
+        .iter()
+        .map(|gacha_type| (*gacha_type).into())
+        .collect()
+}
+
+/// 所有账号的记录总数
+pub(crate) fn record_count(uigf: &UigfV4) -> usize {
+    fn count<A: GachaAccount>(accounts: &Option<Vec<A>>) -> usize {
+        accounts
+            .iter()
+            .flatten()
+            .map(|account| account.list().len())
+            .sum()
+    }
+    count(&uigf.hk4e) + count(&uigf.hkrpg) + count(&uigf.nap)
+}
+
+/// 将各卡池的记录合并为一个账号
+pub(crate) fn merge_pools(
+    game_type: GameType,
+    uigfs: Vec<UigfV4>,
+    info: Info,
+) -> Result<UigfV4, Box<dyn Error + Send + Sync>> {
+    fn merge_accounts<A: GachaAccount>(
+        uigfs: Vec<UigfV4>,
+    ) -> Result<UigfV4, Box<dyn Error + Send + Sync>> {
+        let data = uigfs
+            .iter()
+            .filter_map(|uigf_data| A::accounts(uigf_data).cloned())
+            .flatten()
+            .collect::<Vec<_>>();
+        let header = data.first().ok_or("No data found")?.header();
+        Ok(A::into_uigf(vec![A::new(
+            header,
+            data.into_iter().flat_map(A::into_list).collect(),
+        )]))
+    }
+
+    Ok(match game_type {
+        GameType::Hk4eCN | GameType::Hk4eGlobal => merge_accounts::<Hk4e>(uigfs)?,
+        GameType::HkrpgCN | GameType::HkrpgGlobal => merge_accounts::<Hkrpg>(uigfs)?,
+        GameType::NapCN | GameType::NapGlobal => merge_accounts::<Nap>(uigfs)?,
+    }
+    .with_info(info))
+}
+
+#[cfg(all(feature = "http", feature = "discovery-windows"))]
 pub fn get_uigf(game_type: GameType, gacha_type: GachaType) -> Result<UigfV4, Box<dyn Error>> {
     get_uigf_with_gacha_log(gacha_type, vec![get_gacha_log(game_type, gacha_type)?])
 }
 
+#[cfg(feature = "http")]
 pub fn get_uigf_with_url(gacha_type: GachaType, url: Url) -> Result<UigfV4, Box<dyn Error>> {
-    get_uigf_with_gacha_log(gacha_type, vec![get_gacha_log_with_url(gacha_type, url)?])
+    GachaClient::new().get_uigf(gacha_type, url)
+}
+
+pub fn get_uigf_with_url_with_transport<T: Transport + ?Sized>(
+    transport: &T,
+    gacha_type: GachaType,
+    url: Url,
+) -> Result<UigfV4, Box<dyn Error>> {
+    get_uigf_with_url_with_options(transport, gacha_type, url, &FetchOptions::new())
+}
+
+/// 按选项获取单个卡池，忽略 `options` 中的并发数
+pub fn get_uigf_with_url_with_options<T: Transport + ?Sized>(
+    transport: &T,
+    gacha_type: GachaType,
+    url: Url,
+    options: &FetchOptions,
+) -> Result<UigfV4, Box<dyn Error>> {
+    Ok(fetch_pool(
+        transport,
+        &Pool {
+            gacha_type,
+            since: None,
+        },
+        url,
+        &RateLimiter::new(options.request_interval),
+        options,
+    )?
+    .with_info(options.export_info()))
 }
 
+/// 档案信息与 [`Info::new`] 相同，可使用 [`UigfV4::with_info`] 替换
 pub fn get_uigf_with_gacha_log(
     gacha_type: GachaType,
     gacha_log: Vec<ResponseData>,
 ) -> Result<UigfV4, Box<dyn Error>> {
+    Ok(uigf_from_gacha_log(gacha_type, gacha_log, Info::new()))
+}
+
+pub(crate) fn uigf_from_gacha_log(
+    gacha_type: GachaType,
+    gacha_log: Vec<ResponseData>,
+    info: Info,
+) -> UigfV4 {
     fn collect_gacha_log<T: TryFrom<ResponseData, Error = Box<dyn Error>>>(
         gacha_log: Vec<ResponseData>,
     ) -> Option<Vec<T>> {
@@ -111,26 +363,58 @@ pub fn get_uigf_with_gacha_log(
         GachaType::Nap(_) => (None, None, collect_gacha_log::<Nap>(gacha_log)),
     };
 
-    Ok(UigfV4 {
-        info: Info::new(),
+    UigfV4 {
+        info,
         hk4e,
         hkrpg,
         nap,
-    })
+    }
 }
 
+#[cfg(all(feature = "http", feature = "discovery-windows"))]
 pub fn get_gacha_log(
     game_type: GameType,
     gacha_type: GachaType,
 ) -> Result<ResponseData, Box<dyn Error>> {
-    get_gacha_log_with_url(gacha_type, get_gacha_url(game_type)?)
+    get_gacha_log_with_url(gacha_type, crate::get_gacha_url(game_type)?)
 }
 
+#[cfg(feature = "http")]
 pub fn get_gacha_log_with_url(
     gacha_type: GachaType,
     url: Url,
 ) -> Result<ResponseData, Box<dyn Error>> {
-    let mut url = filter_url(url);
+    GachaClient::new().get_gacha_log(gacha_type, url)
+}
+
+pub fn get_gacha_log_with_url_with_transport<T: Transport + ?Sized>(
+    transport: &T,
+    gacha_type: GachaType,
+    url: Url,
+) -> Result<ResponseData, Box<dyn Error>> {
+    get_gacha_log_with_url_with_options(transport, gacha_type, url, &FetchOptions::new())
+}
+
+/// 按选项获取单个卡池的原始记录，忽略 `options` 中的并发数
+pub fn get_gacha_log_with_url_with_options<T: Transport + ?Sized>(
+    transport: &T,
+    gacha_type: GachaType,
+    url: Url,
+    options: &FetchOptions,
+) -> Result<ResponseData, Box<dyn Error>> {
+    fetch_gacha_log(
+        transport,
+        gacha_type,
+        gacha_log_url(gacha_type, url, options),
+        None,
+        &RateLimiter::new(options.request_interval),
+        options,
+    )
+}
+
+/// 请求指定卡池第一页的链接
+pub(crate) fn gacha_log_url(gacha_type: GachaType, url: Url, options: &FetchOptions) -> Url {
+    let mut url = filter_url(url, &options.query_keys);
     let gacha_type = match gacha_type {
         GachaType::Hk4e(t) => t.to_string(),
         GachaType::Hkrpg(t) => t.to_string(),
@@ -139,6 +423,6 @@ pub fn get_gacha_log_with_url(
     url.query_pairs_mut()
         .append_pair("gacha_type", &gacha_type)
         .append_pair("real_gacha_type", &gacha_type)
-        .append_pair("size", "20");
-    Ok(fetch_gacha_log(url).unwrap())
+        .append_pair("size", &options.page_size.to_string());
+    url
 }
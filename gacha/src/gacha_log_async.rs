@@ -0,0 +1,199 @@
+//! 异步获取抽卡记录，不依赖特定的异步运行时
+//!
+//! 翻页的状态只保存在返回的 future 中，在任意 `.await` 处丢弃 future 即可取消，
+//! 不会留下后台任务，已获取的页也会一并丢弃；也可以使用 [`FetchOptions::cancellation`]
+//! 取消并取回已获取的记录。各卡池总是逐个获取，忽略 `options` 中的并发数
+
+use crate::{
+    gacha_log::{gacha_log_url, merge_pools, pools, record_count, uigf_from_gacha_log},
+    req::{Pager, RateLimiter, ResponseData},
+    AsyncTransport, Cancelled, FetchOptions, GachaType, GameType, ProgressEvent,
+};
+use std::error::Error;
+use uigf::{Info, UigfV4};
+use url::Url;
+
+/// [`get_uigf_with_url_all_with_transport`](crate::get_uigf_with_url_all_with_transport) 的异步版本
+pub async fn get_uigf_with_url_all_async<T: AsyncTransport + Sync>(
+    transport: &T,
+    game_type: GameType,
+    url: Url,
+) -> Result<UigfV4, Box<dyn Error + Send + Sync>> {
+    get_uigf_with_url_all_with_options_async(transport, game_type, url, &FetchOptions::new()).await
+}
+
+/// [`get_uigf_with_url_all_with_options`](crate::get_uigf_with_url_all_with_options) 的异步版本
+///
+/// 取消时返回 [`Cancelled`]，其中包含已获取的记录
+pub async fn get_uigf_with_url_all_with_options_async<T: AsyncTransport + Sync>(
+    transport: &T,
+    game_type: GameType,
+    url: Url,
+    options: &FetchOptions,
+) -> Result<UigfV4, Box<dyn Error + Send + Sync>> {
+    let limiter = RateLimiter::new(options.request_interval);
+    let mut uigfs = Vec::new();
+    let mut cancelled = false;
+    for gacha_type in pools(game_type) {
+        match fetch_pool_async(transport, gacha_type, url.clone(), &limiter, options).await {
+            Ok(uigf) => uigfs.push(uigf),
+            Err(error) => {
+                let error = error.downcast::<Cancelled>()?;
+                uigfs.extend(error.partial);
+                cancelled = true;
+                break;
+            }
+        }
+    }
+
+    let uigf = merge_pools(game_type, uigfs, options.export_info());
+    if cancelled {
+        return Err(Box::new(Cancelled { partial: uigf.ok() }));
+    }
+    let uigf = uigf?;
+    options.report(ProgressEvent::ExportFinished {
+        count: record_count(&uigf),
+    });
+    Ok(uigf)
+}
+
+/// [`get_uigf_with_url_with_transport`](crate::get_uigf_with_url_with_transport) 的异步版本
+pub async fn get_uigf_with_url_async<T: AsyncTransport + Sync>(
+    transport: &T,
+    gacha_type: GachaType,
+    url: Url,
+) -> Result<UigfV4, Box<dyn Error + Send + Sync>> {
+    get_uigf_with_url_with_options_async(transport, gacha_type, url, &FetchOptions::new()).await
+}
+
+/// [`get_uigf_with_url_with_options`](crate::get_uigf_with_url_with_options) 的异步版本
+pub async fn get_uigf_with_url_with_options_async<T: AsyncTransport + Sync>(
+    transport: &T,
+    gacha_type: GachaType,
+    url: Url,
+    options: &FetchOptions,
+) -> Result<UigfV4, Box<dyn Error + Send + Sync>> {
+    let limiter = RateLimiter::new(options.request_interval);
+    Ok(
+        fetch_pool_async(transport, gacha_type, url, &limiter, options)
+            .await?
+            .with_info(options.export_info()),
+    )
+}
+
+/// [`get_gacha_log_with_url_with_transport`](crate::get_gacha_log_with_url_with_transport) 的异步版本
+pub async fn get_gacha_log_with_url_async<T: AsyncTransport + Sync>(
+    transport: &T,
+    gacha_type: GachaType,
+    url: Url,
+) -> Result<ResponseData, Box<dyn Error + Send + Sync>> {
+    get_gacha_log_with_url_with_options_async(transport, gacha_type, url, &FetchOptions::new())
+        .await
+}
+
+/// [`get_gacha_log_with_url_with_options`](crate::get_gacha_log_with_url_with_options) 的异步版本
+pub async fn get_gacha_log_with_url_with_options_async<T: AsyncTransport + Sync>(
+    transport: &T,
+    gacha_type: GachaType,
+    url: Url,
+    options: &FetchOptions,
+) -> Result<ResponseData, Box<dyn Error + Send + Sync>> {
+    let limiter = RateLimiter::new(options.request_interval);
+    fetch_gacha_log_async(transport, gacha_type, url, &limiter, options).await
+}
+
+async fn fetch_pool_async<T: AsyncTransport + Sync>(
+    transport: &T,
+    gacha_type: GachaType,
+    url: Url,
+    limiter: &RateLimiter,
+    options: &FetchOptions,
+) -> Result<UigfV4, Box<dyn Error + Send + Sync>> {
+    let gacha_log = fetch_gacha_log_async(transport, gacha_type, url, limiter, options).await?;
+    Ok(uigf_from_gacha_log(
+        gacha_type,
+        vec![gacha_log],
+        Info::new(),
+    ))
+}
+
+/// [`fetch_gacha_log`](crate::req::fetch_gacha_log) 的异步版本
+async fn fetch_gacha_log_async<T: AsyncTransport + Sync>(
+    transport: &T,
+    gacha_type: GachaType,
+    url: Url,
+    limiter: &RateLimiter,
+    options: &FetchOptions,
+) -> Result<ResponseData, Box<dyn Error + Send + Sync>> {
+    options.report(ProgressEvent::PoolStarted { gacha_type });
+    let mut pager = Pager::new(gacha_log_url(gacha_type, url, options), None)?;
+    if let Err(error) = fetch_pages_async(transport, gacha_type, &mut pager, limiter, options).await
+    {
+        if !error.is::<Cancelled>() {
+            return Err(error);
+        }
+        let partial = pager
+            .finish()
+            .ok()
+            .map(|data| uigf_from_gacha_log(gacha_type, vec![data], Info::new()));
+        return Err(Box::new(Cancelled { partial }));
+    }
+    let data = pager.finish()?;
+    options.report(ProgressEvent::PoolFinished {
+        gacha_type,
+        count: data.list.len(),
+    });
+    Ok(data)
+}
+
+/// [`fetch_pages`](crate::req::fetch_pages) 的异步版本
+async fn fetch_pages_async<T: AsyncTransport + Sync>(
+    transport: &T,
+    gacha_type: GachaType,
+    pager: &mut Pager,
+    limiter: &RateLimiter,
+    options: &FetchOptions,
+) -> Result<(), Box<dyn Error + Send + Sync>> {
+    let mut first = true;
+    while let Some(url) = pager.next_url() {
+        if !first {
+            options.sleep_async(options.page_interval).await?;
+        }
+        first = false;
+        if options.is_cancelled() {
+            return Err(Box::new(Cancelled { partial: None }));
+        }
+
+        let fetched = pager.data().map_or(0, |data| data.list.len());
+        let mut attempt = 1;
+        loop {
+            options.sleep_async(limiter.reserve()).await?;
+            let (error, from_transport) = match transport.get(&url).await {
+                Ok(body) => match pager.push(&body) {
+                    Ok(()) => break,
+                    Err(error) => (error, false),
+                },
+                Err(error) => (error, true),
+            };
+            let Some(delay) = options.retry.backoff(attempt, &*error, from_transport) else {
+                return Err(error);
+            };
+            attempt += 1;
+            options.report(ProgressEvent::RetryScheduled {
+                gacha_type,
+                attempt,
+                delay,
+                error: error.to_string(),
+            });
+            options.sleep_async(delay).await?;
+        }
+
+        let page = pager.data().map_or(&[][..], |data| &data.list[fetched..]);
+        options.report(ProgressEvent::PageFetched {
+            gacha_type,
+            count: page.len(),
+            newest_time: page.first().map(|record| record.time.clone()),
+        });
+    }
+    Ok(())
+}
@@ -1,4 +1,6 @@
-use crate::{req::check_url, GameType};
+#[cfg(feature = "discovery-windows")]
+use crate::GameType;
+use crate::{req::check_url, Transport, DEFAULT_QUERY_KEYS};
 use regex_lite::Regex;
 use std::{
     error::Error,
@@ -8,6 +10,8 @@ use std::{
 };
 use url::Url;
 
+/// 仅支持 Windows，其他平台总是返回错误
+#[cfg(feature = "discovery-windows")]
 pub fn get_gacha_url(game_type: GameType) -> Result<Url, Box<dyn Error>> {
     let log_path = get_local_app_data_low_folder()?.join(match game_type {
         GameType::Hk4eCN => "miHoYo/原神/output_log.txt",
@@ -29,17 +33,51 @@ pub fn get_gacha_url_with_log_data(log_data: String) -> Result<Url, Box<dyn Erro
     get_gacha_url_with_game_data_path(&captures[0])
 }
 
+/// 未启用 `http` 时不会请求接口验证链接，直接返回最新的链接
 pub fn get_gacha_url_with_game_data_path(
     game_data_path: impl AsRef<Path>,
 ) -> Result<Url, Box<dyn Error>> {
+    #[cfg(feature = "http")]
+    return crate::GachaClient::new().get_gacha_url_with_game_data_path(game_data_path);
+    #[cfg(not(feature = "http"))]
+    return get_gacha_urls_with_game_data_path(game_data_path)?
+        .into_iter()
+        .next()
+        .map(|url| crate::req::filter_url(url, DEFAULT_QUERY_KEYS))
+        .ok_or("No valid URL found".into());
+}
+
+/// 使用指定的请求方式验证链接，返回最新的有效链接
+pub fn get_gacha_url_with_game_data_path_with_transport<T: Transport + ?Sized>(
+    transport: &T,
+    game_data_path: impl AsRef<Path>,
+) -> Result<Url, Box<dyn Error>> {
+    find_gacha_url(transport, game_data_path, DEFAULT_QUERY_KEYS)
+}
+
+/// 返回最新的有效链接，只保留 `query_keys` 中的查询参数
+pub(crate) fn find_gacha_url<T: Transport + ?Sized, S: AsRef<str>>(
+    transport: &T,
+    game_data_path: impl AsRef<Path>,
+    query_keys: &[S],
+) -> Result<Url, Box<dyn Error>> {
+    get_gacha_urls_with_game_data_path(game_data_path)?
+        .into_iter()
+        .find_map(|url| check_url(transport, url, query_keys))
+        .ok_or("No valid URL found".into())
+}
+
+/// 网页缓存中的所有抽卡链接，从新到旧排列，未经验证
+pub fn get_gacha_urls_with_game_data_path(
+    game_data_path: impl AsRef<Path>,
+) -> Result<Vec<Url>, Box<dyn Error>> {
     let re = Regex::new(r"(https://.+?/api/getGachaLog.+?authkey=.+?end_id=)")?;
 
     // data_2 contains many non-UTF-8 characters, we only need the UTF-8 part
-    String::from_utf8_lossy(&fs::read(
+    Ok(String::from_utf8_lossy(&fs::read(
         get_latest_folder(game_data_path.as_ref().join("webCaches"))?
             .join("Cache/Cache_Data/data_2"),
     )?)
-    .to_string()
     .split("1/0/")
     .collect::<Vec<_>>()
     .into_iter()
@@ -47,10 +85,10 @@ pub fn get_gacha_url_with_game_data_path(
     .filter_map(|line| re.captures(line))
     .filter(|cap| cap.len() >= 1)
     .filter_map(|cap| Url::from_str(&cap[0]).ok())
-    .find_map(check_url)
-    .ok_or("No valid URL found".into())
+    .collect())
 }
 
+#[cfg(all(feature = "discovery-windows", windows))]
 fn get_local_app_data_low_folder() -> Result<PathBuf, Box<dyn Error>> {
     Ok(PathBuf::from(
         windows::Storage::UserDataPaths::GetDefault()?
@@ -59,6 +97,11 @@ fn get_local_app_data_low_folder() -> Result<PathBuf, Box<dyn Error>> {
     ))
 }
 
+#[cfg(all(feature = "discovery-windows", not(windows)))]
+fn get_local_app_data_low_folder() -> Result<PathBuf, Box<dyn Error>> {
+    Err("Finding the gacha URL from game logs is only supported on Windows".into())
+}
+
 fn get_latest_folder(path: impl AsRef<Path>) -> Result<PathBuf, Box<dyn Error>> {
     fs::read_dir(path)?
         .filter_map(Result::ok)
@@ -0,0 +1,122 @@
+//! 增量获取，只请求比已有记录更新的部分
+
+#[cfg(feature = "http")]
+use crate::GachaClient;
+use crate::{
+    gacha_log::{fetch_pools_with_options, pools_of, Pool},
+    Cancelled, FetchOptions, GachaType, ProgressEvent, Transport,
+};
+use std::{collections::HashMap, error::Error};
+use uigf::{cmp_id, Filter, GachaAccount, GachaItem};
+use url::Url;
+
+/// 增量获取账号的抽卡记录，返回合并了新记录的账号
+///
+/// 各卡池遇到账号中已有的最新记录即停止翻页，链接须属于同一账号
+#[cfg(feature = "http")]
+pub fn get_account_with_url_incremental<A>(account: &A, url: Url) -> Result<A, Box<dyn Error>>
+where
+    A: GachaAccount,
+    <A::Item as GachaItem>::GachaType: Into<GachaType>,
+{
+    GachaClient::new().get_account_incremental(account, url)
+}
+
+pub fn get_account_with_url_incremental_with_options<A, T>(
+    transport: &T,
+    account: &A,
+    url: Url,
+    options: &FetchOptions,
+) -> Result<A, Box<dyn Error>>
+where
+    A: GachaAccount,
+    <A::Item as GachaItem>::GachaType: Into<GachaType>,
+    T: Transport + Sync + ?Sized,
+{
+    let mut merged = account.clone();
+    if let Some(new) = get_account_with_url_since_with_options::<A, T>(
+        transport,
+        &newest_ids(account),
+        url,
+        options,
+    )? {
+        merged.merge(new)?;
+    }
+    Ok(merged)
+}
+
+/// 只获取比 `since` 中各卡池最新记录 ID 更新的记录，未列出的卡池从头获取，没有新记录时返回 `None`
+///
+/// 任一卡池获取失败时返回该错误，如 authkey 过期；
+/// 取消时返回的 [`Cancelled`] 中只有最新的一部分记录，与已有记录之间可能有缺口，
+/// 合并后再增量获取会漏掉缺口中的记录
+pub fn get_account_with_url_since_with_options<A, T>(
+    transport: &T,
+    since: &HashMap<GachaType, String>,
+    url: Url,
+    options: &FetchOptions,
+) -> Result<Option<A>, Box<dyn Error>>
+where
+    A: GachaAccount,
+    <A::Item as GachaItem>::GachaType: Into<GachaType>,
+    T: Transport + Sync + ?Sized,
+{
+    let pools = pools_of::<A>()
+        .into_iter()
+        .map(|gacha_type| Pool {
+            gacha_type,
+            since: since.get(&gacha_type).cloned(),
+        })
+        .collect::<Vec<_>>();
+    let fetched = fetch_pools_with_options(transport, &pools, &url, options);
+    if let Some(error) = fetched.error {
+        return Err(error);
+    }
+
+    let mut accounts = fetched
+        .uigfs
+        .iter()
+        .filter_map(A::accounts)
+        .flatten()
+        .filter(|account| !account.list().is_empty())
+        .cloned();
+    let mut merged = accounts.next();
+    if let Some(merged) = &mut merged {
+        for account in accounts {
+            merged.merge(account)?;
+        }
+    }
+    if fetched.cancelled {
+        return Err(Box::new(Cancelled {
+            partial: merged.map(|account| A::into_uigf(vec![account])),
+        }));
+    }
+    options.report(ProgressEvent::ExportFinished {
+        count: merged.as_ref().map_or(0, |account| account.list().len()),
+    });
+    Ok(merged)
+}
+
+/// 账号中各卡池已有的最新记录 ID，原神的角色活动祈愿-2 与角色活动祈愿合并计算
+///
+/// 导入时生成的 ID 与真实 ID 没有先后关系，不计入，见 [`uigf::is_synthetic_id`]
+pub fn newest_ids<A>(account: &A) -> HashMap<GachaType, String>
+where
+    A: GachaAccount,
+    <A::Item as GachaItem>::GachaType: Into<GachaType>,
+{
+    A::Item::gacha_types()
+        .iter()
+        .filter_map(|gacha_type| {
+            let filter = Filter::new().pool(gacha_type);
+            let newest = account
+                .list()
+                .iter()
+                .filter(|item| filter.matches(*item))
+                .map(GachaItem::id)
+                .filter(|id| !uigf::is_synthetic_id(id))
+                .max_by(|a, b| cmp_id(a, b))?;
+            Some(((*gacha_type).into(), newest.to_string()))
+        })
+        .collect()
+}
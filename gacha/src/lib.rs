@@ -1,12 +1,35 @@
+mod cancel;
+mod checkpoint;
+mod client;
+mod endpoint;
+mod error;
 mod gacha_log;
+#[cfg(feature = "async")]
+mod gacha_log_async;
 mod gacha_url;
+mod incremental;
+mod options;
+mod progress;
 mod req;
+mod transport;
+pub use cancel::*;
+pub use checkpoint::*;
+pub use client::*;
+pub use endpoint::*;
+pub use error::*;
 pub use gacha_log::*;
+#[cfg(feature = "async")]
+pub use gacha_log_async::*;
 pub use gacha_url::*;
+pub use incremental::*;
+pub use options::*;
+pub use progress::*;
+pub use req::{GachaRecord, Response, ResponseData};
+pub use transport::*;
 pub use uigf;
 
 #[non_exhaustive]
-#[derive(Debug, Copy, Clone, PartialEq, Hash)]
+#[derive(Debug, Copy, Clone, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum GameType {
     Hk4eCN,
     Hk4eGlobal,
@@ -16,9 +39,53 @@ pub enum GameType {
     NapGlobal,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+impl GameType {
+    pub fn game(&self) -> uigf::Game {
+        match self {
+            GameType::Hk4eCN | GameType::Hk4eGlobal => uigf::Game::Hk4e,
+            GameType::HkrpgCN | GameType::HkrpgGlobal => uigf::Game::Hkrpg,
+            GameType::NapCN | GameType::NapGlobal => uigf::Game::Nap,
+        }
+    }
+
+    pub fn is_global(&self) -> bool {
+        matches!(
+            self,
+            GameType::Hk4eGlobal | GameType::HkrpgGlobal | GameType::NapGlobal
+        )
+    }
+
+    /// 该接口地址所服务的服务器，国服与国际服各有多个服务器共用同一接口
+    pub fn regions(&self) -> Vec<uigf::Region> {
+        uigf::Region::all()
+            .iter()
+            .filter(|region| region.game == self.game() && region.global == self.is_global())
+            .copied()
+            .collect()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum GachaType {
     Hk4e(uigf::hk4e::GachaType),
     Hkrpg(uigf::hkrpg::GachaType),
     Nap(uigf::nap::GachaType),
 }
+
+impl From<uigf::hk4e::GachaType> for GachaType {
+    fn from(value: uigf::hk4e::GachaType) -> Self {
+        GachaType::Hk4e(value)
+    }
+}
+
+impl From<uigf::hkrpg::GachaType> for GachaType {
+    fn from(value: uigf::hkrpg::GachaType) -> Self {
+        GachaType::Hkrpg(value)
+    }
+}
+
+impl From<uigf::nap::GachaType> for GachaType {
+    fn from(value: uigf::nap::GachaType) -> Self {
+        GachaType::Nap(value)
+    }
+}
@@ -0,0 +1,343 @@
+use crate::{ApiError, CancellationToken, Cancelled, ProgressEvent, ProgressHandler};
+use std::{
+    error::Error,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::Sender,
+    },
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use uigf::{Info, InfoBuilder};
+
+/// 请求时默认保留的链接查询参数
+pub const DEFAULT_QUERY_KEYS: &[&str] =
+    &["authkey", "authkey_ver", "sign_type", "game_biz", "lang"];
+
+/// 接口每页最多返回的记录数
+const MAX_PAGE_SIZE: usize = 20;
+
+/// 获取抽卡记录的选项
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FetchOptions {
+    pub(crate) concurrency: usize,
+    pub(crate) request_interval: Duration,
+    pub(crate) page_size: usize,
+    pub(crate) page_interval: Duration,
+    pub(crate) query_keys: Vec<String>,
+    pub(crate) retry: RetryPolicy,
+    pub(crate) progress: Option<ProgressHandler>,
+    pub(crate) cancellation: Option<CancellationToken>,
+    pub(crate) info: InfoBuilder,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FetchOptions {
+    pub fn new() -> Self {
+        Self {
+            concurrency: 1,
+            request_interval: Duration::from_millis(200),
+            page_size: 20,
+            page_interval: Duration::from_millis(500),
+            query_keys: DEFAULT_QUERY_KEYS
+                .iter()
+                .map(|key| key.to_string())
+                .collect(),
+            retry: RetryPolicy::new(),
+            progress: None,
+            cancellation: None,
+            info: InfoBuilder::default(),
+        }
+    }
+
+    /// 同时获取的卡池数，默认为 1，即逐个卡池获取
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// 所有请求之间的最小间隔，同时获取多个卡池时也不会超过该频率，默认为 200 毫秒
+    pub fn request_interval(mut self, request_interval: Duration) -> Self {
+        self.request_interval = request_interval;
+        self
+    }
+
+    /// 每页的记录数，默认为 20，接口每页最多返回 20 条，超出 1 至 20 时取最接近的值
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+        self
+    }
+
+    /// 请求时保留的链接查询参数，默认为 [`DEFAULT_QUERY_KEYS`]，其余参数如 `timestamp` 会被移除
+    ///
+    /// `gacha_type`、`size` 与 `end_id` 等翻页参数总是由请求时生成
+    pub fn query_keys<I, S>(mut self, query_keys: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.query_keys = query_keys.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// 同一卡池两页之间的等待时间，默认为 500 毫秒
+    pub fn page_interval(mut self, page_interval: Duration) -> Self {
+        self.page_interval = page_interval;
+        self
+    }
+
+    /// 单页请求失败时的重试策略，默认为 [`RetryPolicy::new`]
+    pub fn retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// 获取过程中每发生一个进度事件便调用一次 `handler`
+    pub fn on_progress(mut self, handler: impl Fn(ProgressEvent) + Send + Sync + 'static) -> Self {
+        self.progress = Some(ProgressHandler::new(handler));
+        self
+    }
+
+    /// 将进度事件发送到通道，接收端关闭后不再发送
+    pub fn progress_sender(self, sender: Sender<ProgressEvent>) -> Self {
+        self.on_progress(move |event| {
+            let _ = sender.send(event);
+        })
+    }
+
+    /// 调用 [`CancellationToken::cancel`] 后停止获取，返回 [`Cancelled`] 错误
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// 导出的档案信息，如导出 App 的名称与版本，未设置的字段与 [`Info::new`] 相同
+    pub fn info(mut self, info: InfoBuilder) -> Self {
+        self.info = info;
+        self
+    }
+
+    /// 导出时的档案信息，时间戳未设置时为当前时间
+    pub(crate) fn export_info(&self) -> Info {
+        self.info.clone().build()
+    }
+
+    pub(crate) fn report(&self, event: ProgressEvent) {
+        if let Some(progress) = &self.progress {
+            progress.report(event);
+        }
+    }
+
+    pub(crate) fn is_cancelled(&self) -> bool {
+        self.cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// 已取消时返回不含记录的 [`Cancelled`]
+    pub(crate) fn check_cancelled(&self) -> Result<(), Box<dyn Error>> {
+        if self.is_cancelled() {
+            return Err(Box::new(Cancelled { partial: None }));
+        }
+        Ok(())
+    }
+
+    /// 等待 `duration`，期间取消时立即返回 [`Cancelled`]
+    pub(crate) fn sleep(&self, duration: Duration) -> Result<(), Box<dyn Error>> {
+        match &self.cancellation {
+            Some(token) if token.sleep(duration) => Err(Box::new(Cancelled { partial: None })),
+            Some(_) => Ok(()),
+            None => {
+                thread::sleep(duration);
+                Ok(())
+            }
+        }
+    }
+
+    /// [`FetchOptions::sleep`] 的异步版本
+    #[cfg(feature = "async")]
+    pub(crate) async fn sleep_async(
+        &self,
+        duration: Duration,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        if duration.is_zero() {
+            return Ok(());
+        }
+        match &self.cancellation {
+            Some(token) if token.sleep_async(duration).await => {
+                Err(Box::new(Cancelled { partial: None }))
+            }
+            Some(_) => Ok(()),
+            None => {
+                futures_timer::Delay::new(duration).await;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// 请求失败时的重试策略
+///
+/// 请求方式返回的错误（如网络错误）总会重试，接口返回错误时仅重试指定的 retcode，
+/// 无法解析的响应与缺少数据等其他错误不会重试；重试只针对失败的那一页，已获取的页不会重新请求
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    retryable_retcodes: Vec<i64>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RetryPolicy {
+    /// 最多请求 5 次，等待时间从 1 秒开始翻倍，不超过 30 秒，重试 retcode -110（访问过于频繁）
+    pub fn new() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            retryable_retcodes: vec![-110],
+        }
+    }
+
+    /// 不重试
+    pub fn none() -> Self {
+        Self::new().max_attempts(1)
+    }
+
+    /// 每页最多请求的次数，包括第一次请求
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// 第一次重试前的等待时间，之后每次翻倍
+    pub fn base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// 等待时间的上限
+    pub fn max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// 需要重试的接口 retcode
+    pub fn retryable_retcodes(mut self, retryable_retcodes: impl Into<Vec<i64>>) -> Self {
+        self.retryable_retcodes = retryable_retcodes.into();
+        self
+    }
+
+    /// 第 `attempt` 次请求失败后的等待时间，不再重试时返回 `None`
+    ///
+    /// `from_transport` 表示错误来自请求方式而非响应内容；
+    /// 等待时间在指数退避的一半到全部之间随机，避免多个请求同时重试
+    pub(crate) fn backoff(
+        &self,
+        attempt: u32,
+        error: &(dyn Error + 'static),
+        from_transport: bool,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        let retryable = from_transport
+            || error
+                .downcast_ref::<ApiError>()
+                .is_some_and(|error| self.retryable_retcodes.contains(&error.retcode));
+        if !retryable {
+            return None;
+        }
+
+        let delay = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt - 1))
+            .min(self.max_delay);
+        Some(delay.mul_f64(0.5 + jitter() / 2.0))
+    }
+}
+
+/// `[0, 1)` 间的随机数，使用以当前时间与调用次数为种子的 xorshift64*
+fn jitter() -> f64 {
+    static CALLS: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as u64);
+    let mut x = (nanos ^ CALLS.fetch_add(0x9e37_79b9_7f4a_7c15, Ordering::Relaxed)) | 1;
+    x ^= x >> 12;
+    x ^= x << 25;
+    x ^= x >> 27;
+    (x.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy::new()
+            .base_delay(Duration::from_secs(1))
+            .max_delay(Duration::from_secs(4))
+    }
+
+    fn api_error(retcode: i64) -> ApiError {
+        ApiError {
+            retcode,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn retries_transport_errors_and_configured_retcodes() {
+        let io = std::io::Error::from(std::io::ErrorKind::TimedOut);
+        assert!(policy().backoff(1, &io, true).is_some());
+        let message: Box<dyn Error> = "connection reset".into();
+        assert!(policy().backoff(1, &*message, true).is_some());
+        assert!(policy().backoff(1, &api_error(-110), false).is_some());
+    }
+
+    #[test]
+    fn does_not_retry_response_errors() {
+        assert!(policy().backoff(1, &api_error(-101), false).is_none());
+        let no_data: Box<dyn Error> = "No data found".into();
+        assert!(policy().backoff(1, &*no_data, false).is_none());
+        let json = serde_json::from_str::<serde_json::Value>("{").unwrap_err();
+        assert!(policy().backoff(1, &json, false).is_none());
+    }
+
+    #[test]
+    fn delays_grow_within_bounds() {
+        let error = api_error(-110);
+        for (attempt, full) in [(1, 1000), (2, 2000), (3, 4000), (4, 4000)] {
+            let delay = policy().backoff(attempt, &error, false).unwrap();
+            let full = Duration::from_millis(full);
+            assert!(delay >= full / 2 && delay <= full, "{attempt}: {delay:?}");
+        }
+        assert!(policy().backoff(5, &error, false).is_none());
+    }
+
+    #[test]
+    fn jitter_is_in_range_and_varies() {
+        let samples = (0..100).map(|_| jitter()).collect::<Vec<_>>();
+        assert!(samples.iter().all(|x| (0.0..1.0).contains(x)));
+        assert!(samples.windows(2).any(|pair| pair[0] != pair[1]));
+    }
+
+    #[test]
+    fn page_size_is_clamped() {
+        assert_eq!(FetchOptions::new().page_size(0).page_size, 1);
+        assert_eq!(FetchOptions::new().page_size(5).page_size, 5);
+        assert_eq!(FetchOptions::new().page_size(100).page_size, MAX_PAGE_SIZE);
+    }
+}
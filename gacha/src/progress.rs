@@ -0,0 +1,77 @@
+use crate::GachaType;
+use std::{
+    fmt::{self, Debug, Formatter},
+    hash::{Hash, Hasher},
+    sync::Arc,
+    time::Duration,
+};
+
+/// 获取抽卡记录时的进度
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ProgressEvent {
+    /// 开始获取卡池
+    PoolStarted { gacha_type: GachaType },
+    /// 获取了一页记录
+    PageFetched {
+        gacha_type: GachaType,
+        /// 本页的记录数
+        count: usize,
+        /// 本页最新一条记录的时间
+        newest_time: Option<String>,
+    },
+    /// 请求失败，等待后重试
+    RetryScheduled {
+        gacha_type: GachaType,
+        /// 即将进行第几次请求
+        attempt: u32,
+        delay: Duration,
+        error: String,
+    },
+    /// 卡池获取完毕
+    PoolFinished {
+        gacha_type: GachaType,
+        /// 卡池的记录总数
+        count: usize,
+    },
+    /// 所有卡池获取完毕
+    ExportFinished {
+        /// 导出的记录总数
+        count: usize,
+    },
+}
+
+/// 接收进度的回调，可能在多个线程中同时调用
+#[derive(Clone)]
+pub struct ProgressHandler(Arc<dyn Fn(ProgressEvent) + Send + Sync>);
+
+impl ProgressHandler {
+    pub fn new(handler: impl Fn(ProgressEvent) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(handler))
+    }
+
+    pub(crate) fn report(&self, event: ProgressEvent) {
+        (self.0)(event)
+    }
+}
+
+impl Debug for ProgressHandler {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("ProgressHandler")
+    }
+}
+
+/// 指向同一回调时相等
+impl PartialEq for ProgressHandler {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for ProgressHandler {}
+
+impl Hash for ProgressHandler {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        Arc::as_ptr(&self.0).cast::<()>().hash(state)
+    }
+}
@@ -72,3 +72,12 @@ impl TryFrom<GachaRecord> for NapItem {
         })
     }
 }
+
+/// 测试用的角色活动祈愿记录
+#[cfg(test)]
+pub(crate) fn record(uid: &str, id: &str) -> GachaRecord {
+    serde_json::from_str(&format!(
+        r#"{{"uid":"{uid}","gacha_type":"301","item_id":"","time":"2024-01-01 12:00:00","lang":"zh-cn","id":"{id}"}}"#
+    ))
+    .unwrap()
+}
@@ -1,29 +1,36 @@
 mod gacha_record;
+mod rate_limit;
 mod response;
+use crate::{
+    gacha_log::uigf_from_gacha_log, ApiError, Cancelled, FetchOptions, GachaType, ProgressEvent,
+    Transport,
+};
+use std::error::Error;
+use uigf::{cmp_id, Info};
 use url::Url;
 
 pub use gacha_record::*;
+pub(crate) use rate_limit::*;
 pub use response::*;
 
-pub(crate) fn check_url(url: Url) -> Option<Url> {
-    let response = minreq::get(url.clone()).send().ok()?;
-    let json: serde_json::Value = response.json().ok()?;
+pub(crate) fn check_url<T: Transport + ?Sized, S: AsRef<str>>(
+    transport: &T,
+    url: Url,
+    query_keys: &[S],
+) -> Option<Url> {
+    let json: serde_json::Value = serde_json::from_str(&transport.get(&url).ok()?).ok()?;
     if json["retcode"].as_i64() == Some(0) {
-        Some(filter_url(url))
+        Some(filter_url(url, query_keys))
     } else {
         None
     }
 }
 
-pub(crate) fn filter_url(mut url: Url) -> Url {
+/// 只保留 `query_keys` 中的查询参数
+pub(crate) fn filter_url<S: AsRef<str>>(mut url: Url, query_keys: &[S]) -> Url {
     let query = url
         .query_pairs()
-        .filter(|(key, _)| {
-            matches!(
-                key.as_ref(),
-                "authkey" | "authkey_ver" | "sign_type" | "game_biz" | "lang"
-            )
-        })
+        .filter(|(key, _)| query_keys.iter().any(|k| k.as_ref() == key))
         .fold(
             url::form_urlencoded::Serializer::new(String::new()),
             |mut serializer, (key, value)| {
@@ -36,36 +43,341 @@ pub(crate) fn filter_url(mut url: Url) -> Url {
     url
 }
 
-pub(crate) fn fetch_gacha_log(mut url: Url) -> Result<ResponseData, Box<dyn std::error::Error>> {
-    let query = url.query().ok_or("No query found")?.to_string();
-    let response = minreq::get(url.clone()).send()?.json::<Response>()?;
-    if response.retcode != 0 {
-        return Err(response.message.into());
+/// 逐页请求直至取完或遇到 `since` 及更早的记录，每页之间按 `options` 间隔，
+/// 每次请求前还需等待全局限速，单页失败时按 `options` 重试该页；取消时返回包含已获取记录的 [`Cancelled`]
+pub(crate) fn fetch_gacha_log<T: Transport + ?Sized>(
+    transport: &T,
+    gacha_type: GachaType,
+    url: Url,
+    since: Option<&str>,
+    limiter: &RateLimiter,
+    options: &FetchOptions,
+) -> Result<ResponseData, Box<dyn Error>> {
+    options.report(ProgressEvent::PoolStarted { gacha_type });
+    let mut pager = Pager::new(url, since).map_err(unsync)?;
+    let result = fetch_pages(transport, gacha_type, &mut pager, limiter, options, |_| {
+        Ok(())
+    });
+    if let Err(error) = result {
+        if !error.is::<Cancelled>() {
+            return Err(error);
+        }
+        let partial = pager
+            .finish()
+            .ok()
+            .map(|data| uigf_from_gacha_log(gacha_type, vec![data], Info::new()));
+        return Err(Box::new(Cancelled { partial }));
+    }
+    let data = pager.finish().map_err(unsync)?;
+    options.report(ProgressEvent::PoolFinished {
+        gacha_type,
+        count: data.list.len(),
+    });
+    Ok(data)
+}
+
+/// 从 `pager` 当前的位置继续翻页直至取完，每取得一页调用一次 `on_page`
+///
+/// 取消时返回不含记录的 [`Cancelled`]，已获取的页仍保留在 `pager` 中
+pub(crate) fn fetch_pages<T: Transport + ?Sized>(
+    transport: &T,
+    gacha_type: GachaType,
+    pager: &mut Pager,
+    limiter: &RateLimiter,
+    options: &FetchOptions,
+    mut on_page: impl FnMut(&Pager) -> Result<(), Box<dyn Error>>,
+) -> Result<(), Box<dyn Error>> {
+    let mut first = true;
+    while let Some(url) = pager.next_url() {
+        if !first {
+            options.sleep(options.page_interval)?;
+        }
+        first = false;
+        options.check_cancelled()?;
+
+        let fetched = pager.data().map_or(0, |data| data.list.len());
+        let mut attempt = 1;
+        loop {
+            options.sleep(limiter.reserve())?;
+            let (error, from_transport) = match transport.get(&url) {
+                Ok(body) => match pager.push(&body) {
+                    Ok(()) => break,
+                    Err(error) => (unsync(error), false),
+                },
+                Err(error) => (error, true),
+            };
+            let Some(delay) = options.retry.backoff(attempt, &*error, from_transport) else {
+                return Err(error);
+            };
+            attempt += 1;
+            options.report(ProgressEvent::RetryScheduled {
+                gacha_type,
+                attempt,
+                delay,
+                error: error.to_string(),
+            });
+            options.sleep(delay)?;
+        }
+
+        let page = pager.data().map_or(&[][..], |data| &data.list[fetched..]);
+        options.report(ProgressEvent::PageFetched {
+            gacha_type,
+            count: page.len(),
+            newest_time: page.first().map(|record| record.time.clone()),
+        });
+        on_page(pager)?;
+    }
+    Ok(())
+}
+
+/// 同步接口的错误类型不要求 `Send + Sync`
+pub(crate) fn unsync(error: Box<dyn Error + Send + Sync>) -> Box<dyn Error> {
+    error
+}
+
+/// 在线程间传递错误，[`ApiError`] 保留原类型，其他错误只保留错误信息
+pub(crate) fn sendable(error: Box<dyn Error>) -> Box<dyn Error + Send + Sync> {
+    match error.downcast::<ApiError>() {
+        Ok(error) => error,
+        Err(error) => error.to_string().into(),
+    }
+}
+
+/// 翻页状态，只负责拼接链接与合并数据，不发送请求
+pub(crate) struct Pager {
+    url: Url,
+    query: String,
+    since: Option<String>,
+    data: Option<ResponseData>,
+    end_id: Option<String>,
+    finished: bool,
+}
+
+impl Pager {
+    /// 记录按 ID 从新到旧返回，遇到 `since` 及更早的记录时停止翻页并丢弃这些记录
+    pub(crate) fn new(url: Url, since: Option<&str>) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let query = url.query().ok_or("No query found")?.to_string();
+        Ok(Self {
+            url,
+            query,
+            since: since.map(str::to_string),
+            data: None,
+            end_id: None,
+            finished: false,
+        })
+    }
+
+    /// 从已获取的数据与下一页的 `end_id` 继续翻页，已有数据但没有 `end_id` 时视为已取完
+    pub(crate) fn resume(
+        url: Url,
+        data: Option<ResponseData>,
+        end_id: Option<String>,
+    ) -> Result<Self, Box<dyn Error + Send + Sync>> {
+        let mut pager = Self::new(url, None)?;
+        pager.finished = data.is_some() && end_id.is_none();
+        pager.data = data;
+        pager.end_id = end_id;
+        Ok(pager)
+    }
+
+    /// 已获取的数据
+    pub(crate) fn data(&self) -> Option<&ResponseData> {
+        self.data.as_ref()
     }
 
-    let mut all_data = response.data.ok_or("No data found")?;
-    let mut end_id = all_data.list.last().map(|record| record.id.clone());
+    /// 下一页的 `end_id`
+    pub(crate) fn end_id(&self) -> Option<&str> {
+        self.end_id.as_deref()
+    }
 
-    while let Some(id) = end_id {
-        url.set_query(Some(&format!("{query}&end_id={id}")));
+    pub(crate) fn is_finished(&self) -> bool {
+        self.finished
+    }
 
-        let response = minreq::get(url.clone()).send()?.json::<Response>()?;
+    /// 下一页的链接，已取完时返回 `None`
+    pub(crate) fn next_url(&self) -> Option<Url> {
+        if self.finished {
+            return None;
+        }
+        let mut url = self.url.clone();
+        if let Some(id) = &self.end_id {
+            url.set_query(Some(&format!("{}&end_id={id}", self.query)));
+        }
+        Some(url)
+    }
 
+    /// 处理一页的响应正文
+    pub(crate) fn push(&mut self, body: &str) -> Result<(), Box<dyn Error + Send + Sync>> {
+        let response = serde_json::from_str::<Response>(body)?;
         if response.retcode != 0 {
-            return Err(response.message.into());
+            return Err(Box::new(ApiError {
+                retcode: response.retcode,
+                message: response.message,
+            }));
         }
 
-        if let Some(mut data) = response.data {
-            end_id = data.list.last().map(|record| record.id.clone());
-            all_data.list.append(&mut data.list);
-        } else {
-            break;
+        let Some(mut data) = response.data else {
+            if self.data.is_none() {
+                return Err("No data found".into());
+            }
+            self.finished = true;
+            return Ok(());
+        };
+        self.end_id = data.list.last().map(|record| record.id.clone());
+        self.finished = self.end_id.is_none();
+        if let Some(since) = &self.since {
+            if let Some(index) = data
+                .list
+                .iter()
+                .position(|record| cmp_id(&record.id, since).is_le())
+            {
+                data.list.truncate(index);
+                self.finished = true;
+            }
+        }
+        match &mut self.data {
+            Some(all_data) => all_data.list.append(&mut data.list),
+            None => self.data = Some(data),
         }
+        Ok(())
+    }
 
-        std::thread::sleep(std::time::Duration::from_millis(500));
+    pub(crate) fn finish(self) -> Result<ResponseData, Box<dyn Error + Send + Sync>> {
+        let mut all_data = self.data.ok_or("No data found")?;
+        all_data.size = all_data.list.len().to_string();
+        all_data.page = Some("1".to_string());
+        Ok(all_data)
     }
+}
 
-    all_data.size = all_data.list.len().to_string();
-    all_data.page = Some("1".to_string());
-    Ok(all_data)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DEFAULT_QUERY_KEYS;
+
+    const URL: &str = "https://example.com/getGachaLog?authkey=abc&gacha_type=301&size=20";
+
+    fn page(ids: &[&str]) -> String {
+        let list = ids
+            .iter()
+            .map(|id| record("100000001", id))
+            .collect::<Vec<_>>();
+        serde_json::json!({"retcode": 0, "message": "OK", "data": {"size": "20", "list": list}})
+            .to_string()
+    }
+
+    fn ids(pager: &Pager) -> Vec<&str> {
+        pager
+            .data()
+            .map(|data| data.list.iter().map(|record| record.id.as_str()).collect())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn pager_chains_end_id() {
+        let mut pager = Pager::new(Url::parse(URL).unwrap(), None).unwrap();
+        assert_eq!(pager.next_url().unwrap().as_str(), URL);
+
+        pager.push(&page(&["1000", "999"])).unwrap();
+        assert_eq!(pager.end_id(), Some("999"));
+        assert_eq!(
+            pager.next_url().unwrap().query(),
+            Some("authkey=abc&gacha_type=301&size=20&end_id=999")
+        );
+
+        pager.push(&page(&["998"])).unwrap();
+        pager.push(&page(&[])).unwrap();
+        assert!(pager.is_finished());
+        assert!(pager.next_url().is_none());
+        assert_eq!(ids(&pager), ["1000", "999", "998"]);
+
+        let data = pager.finish().unwrap();
+        assert_eq!(data.size, "3");
+        assert_eq!(data.page.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn pager_stops_at_since() {
+        let mut pager = Pager::new(Url::parse(URL).unwrap(), Some("999")).unwrap();
+        pager.push(&page(&["1001", "1000", "999", "998"])).unwrap();
+        assert!(pager.is_finished());
+        assert_eq!(ids(&pager), ["1001", "1000"]);
+    }
+
+    #[test]
+    fn pager_empty_pool() {
+        let mut pager = Pager::new(Url::parse(URL).unwrap(), None).unwrap();
+        pager.push(&page(&[])).unwrap();
+        assert!(pager.is_finished());
+        assert!(pager.finish().unwrap().list.is_empty());
+
+        let mut pager = Pager::new(Url::parse(URL).unwrap(), None).unwrap();
+        assert!(pager
+            .push(r#"{"retcode":0,"message":"OK","data":null}"#)
+            .is_err());
+    }
+
+    #[test]
+    fn pager_api_error() {
+        let mut pager = Pager::new(Url::parse(URL).unwrap(), None).unwrap();
+        let error = pager
+            .push(r#"{"retcode":-101,"message":"authkey timeout","data":null}"#)
+            .unwrap_err();
+        assert_eq!(error.downcast_ref::<ApiError>().unwrap().retcode, -101);
+        assert!(!pager.is_finished());
+        assert!(pager.data().is_none());
+    }
+
+    #[test]
+    fn pager_resume() {
+        let mut first = Pager::new(Url::parse(URL).unwrap(), None).unwrap();
+        first.push(&page(&["1000", "999"])).unwrap();
+        let resumed = Pager::resume(
+            Url::parse(URL).unwrap(),
+            first.data().cloned(),
+            first.end_id().map(str::to_string),
+        )
+        .unwrap();
+        assert_eq!(resumed.next_url(), first.next_url());
+
+        let finished = Pager::resume(Url::parse(URL).unwrap(), first.data().cloned(), None);
+        assert!(finished.unwrap().is_finished());
+    }
+
+    struct Fake(String);
+
+    impl Transport for Fake {
+        fn get(&self, _: &Url) -> Result<String, Box<dyn Error>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[test]
+    fn check_url_filters_query() {
+        let url = Url::parse(
+            "https://example.com/getGachaLog?win_mode=fullscreen&authkey_ver=1&sign_type=2&authkey=a%2Bb&lang=zh-cn&game_biz=hk4e_cn&gacha_type=301&end_id=1",
+        )
+        .unwrap();
+        let checked = check_url(&Fake(page(&[])), url.clone(), DEFAULT_QUERY_KEYS).unwrap();
+        assert_eq!(
+            checked.query(),
+            Some("authkey_ver=1&sign_type=2&authkey=a%2Bb&lang=zh-cn&game_biz=hk4e_cn")
+        );
+
+        let expired = r#"{"retcode":-101,"message":"authkey timeout","data":null}"#;
+        assert!(check_url(&Fake(expired.into()), url.clone(), DEFAULT_QUERY_KEYS).is_none());
+        assert!(check_url(&Fake("<html>".into()), url, DEFAULT_QUERY_KEYS).is_none());
+    }
+
+    #[test]
+    fn filter_url_keeps_configured_keys() {
+        let url =
+            Url::parse("https://example.com/getGachaLog?authkey=abc&lang=zh-cn&region=cn_gf01")
+                .unwrap();
+        assert_eq!(
+            filter_url(url.clone(), &["authkey", "region"]).query(),
+            Some("authkey=abc&region=cn_gf01")
+        );
+        assert_eq!(filter_url(url, &[] as &[&str]).query(), Some(""));
+    }
 }
@@ -0,0 +1,30 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// 全局限速，任意两次请求之间至少间隔 `interval`，可在多个线程间共享
+pub(crate) struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Option<Instant>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            next: Mutex::new(None),
+        }
+    }
+
+    /// 预约下一个可用的时间点，返回距该时间点的等待时间
+    ///
+    /// 由调用方通过 `FetchOptions::sleep` 等待，以便取消时立即停止
+    pub(crate) fn reserve(&self) -> Duration {
+        let mut next = self.next.lock().unwrap();
+        let now = Instant::now();
+        let at = next.map_or(now, |next| next.max(now));
+        *next = Some(at + self.interval);
+        at - now
+    }
+}
@@ -4,6 +4,7 @@ use uigf::{
     hk4e::{Hk4e, Hk4eItem},
     hkrpg::{Hkrpg, HkrpgItem},
     nap::{Nap, NapItem},
+    Game, Region,
 };
 
 #[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
@@ -22,15 +23,22 @@ pub struct ResponseData {
     pub region_time_zone: Option<i64>,
 }
 
-fn get_time_zone_by_uid(uid: &str) -> i64 {
-    // 原神不提供时区信息，根据 UID 判断
-    // 6 => os_usa, 7 => os_euro, _ => os_cht, os_asia, cn_gf01, cn_qd01
-    if uid.starts_with("6") {
-        -5
-    } else if uid.starts_with("7") {
-        1
-    } else {
-        8
+impl ResponseData {
+    /// 先根据接口返回的 `region` 查找服务器，接口未返回或无法识别时根据 UID 判断
+    pub fn region(&self, game: Game) -> Option<Region> {
+        self.region
+            .as_deref()
+            .and_then(|name| Region::from_name(game, name))
+            .or_else(|| Region::from_uid(game, &self.list.first()?.uid))
+    }
+
+    /// 依次根据接口返回的时区、服务器与 UID 判断记录所在的服务器时区，均无法判断时为 UTC+8
+    ///
+    /// 原神的接口不返回时区与服务器，只能根据 UID 判断
+    fn timezone(&self, game: Game) -> i64 {
+        self.region_time_zone
+            .or_else(|| self.region(game).map(|region| region.timezone))
+            .unwrap_or(8)
     }
 }
 
@@ -43,10 +51,9 @@ impl TryFrom<ResponseData> for Hk4e {
         }
         Ok(Self {
             uid: value.list[0].uid.parse()?,
-            timezone: value
-                .region_time_zone
-                .unwrap_or(get_time_zone_by_uid(&value.list[0].uid)),
-            lang: Some(value.list[0].lang.clone()),
+            timezone: value.timezone(Game::Hk4e),
+            region: value.region(Game::Hk4e),
+            lang: Some(value.list[0].lang),
             list: value
                 .list
                 .into_iter()
@@ -63,11 +70,11 @@ impl TryFrom<ResponseData> for Hkrpg {
         if value.list.is_empty() {
             return Err("No data found".into());
         }
-        dbg!(value.clone());
         Ok(Self {
             uid: value.list[0].uid.parse()?,
-            timezone: value.region_time_zone.unwrap(),
-            lang: Some(value.list[0].lang.clone()),
+            timezone: value.timezone(Game::Hkrpg),
+            region: value.region(Game::Hkrpg),
+            lang: Some(value.list[0].lang),
             list: value
                 .list
                 .into_iter()
@@ -86,8 +93,9 @@ impl TryFrom<ResponseData> for Nap {
         }
         Ok(Self {
             uid: value.list[0].uid.parse()?,
-            timezone: value.region_time_zone.unwrap(),
-            lang: Some(value.list[0].lang.clone()),
+            timezone: value.timezone(Game::Nap),
+            region: value.region(Game::Nap),
+            lang: Some(value.list[0].lang),
             list: value
                 .list
                 .into_iter()
@@ -96,3 +104,54 @@ impl TryFrom<ResponseData> for Nap {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::req::record;
+    use uigf::GachaAccount;
+
+    fn data(uid: &str, region: Option<&str>, region_time_zone: Option<i64>) -> ResponseData {
+        ResponseData {
+            page: None,
+            size: "20".into(),
+            list: vec![record(uid, "1")],
+            region: region.map(Into::into),
+            region_time_zone,
+        }
+    }
+
+    #[test]
+    fn timezone_sources() {
+        let cases = [
+            (data("600000001", Some("os_asia"), Some(-5)), Game::Hk4e, -5),
+            (data("800000001", Some("os_usa"), None), Game::Hk4e, -5),
+            (data("700000001", None, None), Game::Hk4e, 1),
+            (data("700000001", Some("unknown"), None), Game::Hk4e, 1),
+            (data("1700000001", None, None), Game::Nap, 8),
+            (data("1500000001", None, None), Game::Nap, 1),
+            // 无法识别的 UID 与此前一致视为 UTC+8
+            (data("000000001", None, None), Game::Hk4e, 8),
+            (data("1900000001", None, None), Game::Nap, 8),
+        ];
+        for (data, game, timezone) in cases {
+            assert_eq!(data.timezone(game), timezone, "{:?}", data.list[0].uid);
+        }
+    }
+
+    #[test]
+    fn region_prefers_name() {
+        let named = data("800000001", Some("os_usa"), None);
+        assert_eq!(named.region(Game::Hk4e).unwrap().name, "os_usa");
+        let unnamed = data("800000001", None, None);
+        assert_eq!(unnamed.region(Game::Hk4e).unwrap().name, "os_asia");
+    }
+
+    #[test]
+    fn account_keeps_region() {
+        let account = Hk4e::try_from(data("800000001", Some("os_usa"), None)).unwrap();
+        assert_eq!(account.region.unwrap().name, "os_usa");
+        let rebuilt = Hk4e::new(account.header(), account.list);
+        assert_eq!(rebuilt.region().unwrap().name, "os_usa");
+    }
+}
@@ -0,0 +1,118 @@
+use std::error::Error;
+#[cfg(feature = "http")]
+use std::time::Duration;
+use url::Url;
+
+/// 发送接口请求的方式，可替换为其他 HTTP 库或离线测试用的假实现
+pub trait Transport {
+    /// 以 GET 请求 `url`，返回响应正文
+    fn get(&self, url: &Url) -> Result<String, Box<dyn Error>>;
+}
+
+impl<T: Transport + ?Sized> Transport for &T {
+    fn get(&self, url: &Url) -> Result<String, Box<dyn Error>> {
+        (**self).get(url)
+    }
+}
+
+impl<T: Transport + ?Sized> Transport for Box<T> {
+    fn get(&self, url: &Url) -> Result<String, Box<dyn Error>> {
+        (**self).get(url)
+    }
+}
+
+/// 默认的请求方式，使用 minreq
+#[cfg(feature = "http")]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct MinreqTransport {
+    timeout: Option<Duration>,
+    user_agent: Option<String>,
+    proxy: Option<String>,
+}
+
+#[cfg(feature = "http")]
+impl MinreqTransport {
+    /// 不设置超时、User-Agent 与代理
+    pub const fn new() -> Self {
+        Self {
+            timeout: None,
+            user_agent: None,
+            proxy: None,
+        }
+    }
+
+    /// 请求的超时时间，minreq 的超时以秒为单位，不足一秒的部分向上取整，至少为 1 秒
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// HTTP 代理，如 `user:password@localhost:1080`
+    pub fn proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+}
+
+#[cfg(feature = "http")]
+impl Transport for MinreqTransport {
+    fn get(&self, url: &Url) -> Result<String, Box<dyn Error>> {
+        let mut request = minreq::get(url.as_str());
+        if let Some(timeout) = self.timeout {
+            request = request.with_timeout(timeout_secs(timeout));
+        }
+        if let Some(user_agent) = &self.user_agent {
+            request = request.with_header("User-Agent", user_agent);
+        }
+        if let Some(proxy) = &self.proxy {
+            request = request.with_proxy(minreq::Proxy::new(proxy)?);
+        }
+        Ok(request.send()?.as_str()?.to_string())
+    }
+}
+
+/// minreq 的超时以秒为单位，不足一秒的部分向上取整，至少为 1 秒
+#[cfg(feature = "http")]
+fn timeout_secs(timeout: Duration) -> u64 {
+    (timeout.as_secs() + u64::from(timeout.subsec_nanos() > 0)).max(1)
+}
+
+/// 异步请求方式，如基于 reqwest 的实现
+#[cfg(feature = "async")]
+pub trait AsyncTransport {
+    /// 以 GET 请求 `url`，返回响应正文
+    fn get(
+        &self,
+        url: &Url,
+    ) -> impl std::future::Future<Output = Result<String, Box<dyn Error + Send + Sync>>> + Send;
+}
+
+#[cfg(feature = "async")]
+impl<T: AsyncTransport + Sync> AsyncTransport for &T {
+    fn get(
+        &self,
+        url: &Url,
+    ) -> impl std::future::Future<Output = Result<String, Box<dyn Error + Send + Sync>>> + Send
+    {
+        (**self).get(url)
+    }
+}
+
+#[cfg(all(test, feature = "http"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timeout_rounds_up_to_seconds() {
+        assert_eq!(timeout_secs(Duration::ZERO), 1);
+        assert_eq!(timeout_secs(Duration::from_millis(1)), 1);
+        assert_eq!(timeout_secs(Duration::from_secs(1)), 1);
+        assert_eq!(timeout_secs(Duration::from_millis(1001)), 2);
+        assert_eq!(timeout_secs(Duration::from_secs(30)), 30);
+    }
+}
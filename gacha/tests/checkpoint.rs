@@ -0,0 +1,113 @@
+mod common;
+
+use common::{options, records, FakeTransport, HK4E_URL, HKRPG_URL};
+use gacha::{
+    get_uigf_with_url_all_with_checkpoint, resume_uigf_with_url_all, Checkpoint, GameType,
+};
+use std::{fs, path::PathBuf};
+use url::Url;
+
+/// 角色活动祈愿返回一页记录，其他卡池为空
+fn transport() -> FakeTransport {
+    FakeTransport::default().page("301", "", records("301", &["15"]))
+}
+
+/// 每个测试使用独立的文件，避免并行运行时互相影响
+fn path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("gacha-{}-{name}", std::process::id()));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+#[test]
+fn existing_checkpoint_is_not_overwritten() {
+    let path = path("existing.json");
+    Checkpoint::new(GameType::HkrpgCN).save(&path).unwrap();
+    let before = fs::read_to_string(&path).unwrap();
+
+    let result = get_uigf_with_url_all_with_checkpoint(
+        &transport(),
+        GameType::Hk4eCN,
+        Url::parse(HK4E_URL).unwrap(),
+        &options(),
+        &path,
+    );
+    assert!(result.is_err());
+    assert_eq!(fs::read_to_string(&path).unwrap(), before);
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn fresh_export_removes_checkpoint() {
+    let path = path("fresh.json");
+    let uigf = get_uigf_with_url_all_with_checkpoint(
+        &transport(),
+        GameType::Hk4eCN,
+        Url::parse(HK4E_URL).unwrap(),
+        &options(),
+        &path,
+    )
+    .unwrap();
+    assert_eq!(uigf.hk4e.unwrap()[0].list.len(), 1);
+    assert!(!path.exists());
+}
+
+#[test]
+fn save_appends_tmp_suffix() {
+    let path = path("progress.tmp");
+    let checkpoint = Checkpoint::new(GameType::Hk4eCN);
+    checkpoint.save(&path).unwrap();
+    checkpoint.save(&path).unwrap();
+
+    assert!(Checkpoint::load(&path).is_ok());
+    assert!(!path.with_extension("tmp.tmp").exists());
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn resume_rejects_url_of_another_game() {
+    let path = path("mismatch.json");
+    Checkpoint::new(GameType::Hk4eCN).save(&path).unwrap();
+
+    let result = resume_uigf_with_url_all(
+        &transport(),
+        Url::parse(HKRPG_URL).unwrap(),
+        &options(),
+        &path,
+    );
+    assert!(result.is_err());
+    assert!(path.exists());
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn resume_finishes_export() {
+    let path = path("resume.json");
+    Checkpoint::new(GameType::Hk4eCN).save(&path).unwrap();
+
+    let uigf = resume_uigf_with_url_all(
+        &transport(),
+        Url::parse(HK4E_URL).unwrap(),
+        &options(),
+        &path,
+    )
+    .unwrap();
+    assert_eq!(uigf.hk4e.unwrap()[0].list.len(), 1);
+    assert!(!path.exists());
+}
+
+#[test]
+fn resume_accepts_mirror_url() {
+    let path = path("mirror.json");
+    Checkpoint::new(GameType::Hk4eCN).save(&path).unwrap();
+
+    let uigf = resume_uigf_with_url_all(
+        &transport(),
+        Url::parse("https://mirror.example.com/getGachaLog?authkey=abc&lang=zh-cn").unwrap(),
+        &options(),
+        &path,
+    )
+    .unwrap();
+    assert_eq!(uigf.hk4e.unwrap()[0].list.len(), 1);
+    assert!(!path.exists());
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use common::{options, query, FakeTransport, HK4E_URL};
+use gacha::{GachaClient, GachaType};
+use uigf::{hk4e, ExportTimestamp, Info};
+use url::Url;
+
+fn wish() -> GachaType {
+    GachaType::Hk4e(hk4e::GachaType::CharacterEventWish)
+}
+
+#[test]
+fn url_is_unchanged_by_default() {
+    let client = GachaClient::builder_with_transport(FakeTransport::default()).build();
+    let url = Url::parse(HK4E_URL).unwrap();
+    assert_eq!(client.url(url.clone()), url);
+}
+
+#[test]
+fn base_url_replaces_everything_but_the_query() {
+    let client = GachaClient::builder_with_transport(FakeTransport::default())
+        .options(options())
+        .base_url(Url::parse("http://localhost:8080/mirror/getGachaLog?ignored=1").unwrap())
+        .build();
+    client
+        .get_gacha_log(wish(), Url::parse(HK4E_URL).unwrap())
+        .unwrap();
+
+    let url = &client.transport().urls()[0];
+    assert_eq!(
+        url[..url::Position::AfterPath],
+        *"http://localhost:8080/mirror/getGachaLog"
+    );
+    assert_eq!(query(url, "authkey"), "abc");
+    assert_eq!(query(url, "ignored"), "");
+}
+
+#[test]
+fn lang_overrides_the_url() {
+    let client = GachaClient::builder_with_transport(FakeTransport::default())
+        .lang("ja-jp")
+        .build();
+    let url = client.url(Url::parse(HK4E_URL).unwrap());
+    assert_eq!(
+        url.query_pairs().filter(|(key, _)| key == "lang").count(),
+        1
+    );
+    assert_eq!(query(&url, "lang"), "ja-jp");
+    assert_eq!(query(&url, "authkey"), "abc");
+
+    let url = client.url(Url::parse("https://example.com/?authkey=abc").unwrap());
+    assert_eq!(query(&url, "lang"), "ja-jp");
+}
+
+#[test]
+fn page_size_is_clamped() {
+    for (page_size, expected) in [(0, "1"), (5, "5"), (20, "20"), (100, "20")] {
+        let client = GachaClient::builder_with_transport(FakeTransport::default())
+            .options(options())
+            .page_size(page_size)
+            .build();
+        client
+            .get_gacha_log(wish(), Url::parse(HK4E_URL).unwrap())
+            .unwrap();
+        assert_eq!(
+            query(&client.transport().urls()[0], "size"),
+            expected,
+            "{page_size}"
+        );
+    }
+}
+
+#[test]
+fn options_replace_earlier_settings() {
+    let client = GachaClient::builder_with_transport(FakeTransport::default())
+        .page_size(5)
+        .options(options())
+        .build();
+    assert_eq!(*client.options(), options());
+}
+
+#[test]
+fn info_is_stored_in_options() {
+    let info = Info::builder()
+        .export_app("app")
+        .export_timestamp(ExportTimestamp::Integer(1704081600));
+    let client = GachaClient::builder_with_transport(FakeTransport::default())
+        .options(options())
+        .info(info.clone())
+        .build();
+    assert_eq!(*client.options(), options().info(info));
+}
+
+#[cfg(feature = "http")]
+#[test]
+fn transport_settings_are_kept() {
+    use gacha::MinreqTransport;
+    use std::time::Duration;
+
+    let client = GachaClient::builder()
+        .page_size(5)
+        .timeout(Duration::from_millis(1500))
+        .user_agent("test")
+        .proxy("localhost:1080")
+        .build();
+    assert_eq!(
+        *client.transport(),
+        MinreqTransport::new()
+            .timeout(Duration::from_millis(1500))
+            .user_agent("test")
+            .proxy("localhost:1080")
+    );
+    assert_eq!(*client.options(), gacha::FetchOptions::new().page_size(5));
+
+    let client = GachaClient::builder()
+        .page_size(5)
+        .transport(FakeTransport::default())
+        .build();
+    assert_eq!(*client.options(), gacha::FetchOptions::new().page_size(5));
+}
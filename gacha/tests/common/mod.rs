@@ -0,0 +1,111 @@
+//! 集成测试共用的模拟接口与响应
+#![allow(dead_code)]
+
+#[cfg(feature = "async")]
+use gacha::AsyncTransport;
+use gacha::{FetchOptions, RetryPolicy, Transport};
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::{collections::HashMap, error::Error, sync::Mutex, time::Duration};
+use url::Url;
+
+pub const HK4E_URL: &str = "https://public-operation-hk4e.mihoyo.com/gacha_info/api/getGachaLog?authkey_ver=1&sign_type=2&authkey=abc&lang=zh-cn&game_biz=hk4e_cn";
+pub const HKRPG_URL: &str = "https://public-operation-hkrpg.mihoyo.com/common/gacha_record/api/getGachaLog?authkey_ver=1&sign_type=2&authkey=abc&lang=zh-cn&game_biz=hkrpg_cn";
+
+/// 按 `gacha_type` 与 `end_id` 返回预先准备的响应，未准备的页返回空列表
+#[derive(Default)]
+pub struct FakeTransport {
+    pages: HashMap<(String, String), Vec<String>>,
+    requests: Mutex<Vec<Url>>,
+}
+
+impl FakeTransport {
+    /// 同一页可以准备多个响应，依次返回，用于模拟失败后重试
+    pub fn page(mut self, gacha_type: &str, end_id: &str, body: String) -> Self {
+        self.pages
+            .entry((gacha_type.into(), end_id.into()))
+            .or_default()
+            .push(body);
+        self
+    }
+
+    /// 已请求的 `gacha_type` 与 `end_id`
+    pub fn requests(&self) -> Vec<(String, String)> {
+        self.urls()
+            .iter()
+            .map(|url| (query(url, "gacha_type"), query(url, "end_id")))
+            .collect()
+    }
+
+    pub fn urls(&self) -> Vec<Url> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn respond(&self, url: &Url) -> String {
+        let key = (query(url, "gacha_type"), query(url, "end_id"));
+        let attempt = {
+            let mut requests = self.requests.lock().unwrap();
+            requests.push(url.clone());
+            requests
+                .iter()
+                .filter(|url| (query(url, "gacha_type"), query(url, "end_id")) == key)
+                .count()
+        };
+        match self.pages.get(&key) {
+            Some(bodies) => bodies[(attempt - 1).min(bodies.len() - 1)].clone(),
+            None => records(&key.0, &[]),
+        }
+    }
+}
+
+impl Transport for FakeTransport {
+    fn get(&self, url: &Url) -> Result<String, Box<dyn Error>> {
+        Ok(self.respond(url))
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncTransport for FakeTransport {
+    fn get(
+        &self,
+        url: &Url,
+    ) -> impl Future<Output = Result<String, Box<dyn Error + Send + Sync>>> + Send {
+        let body = self.respond(url);
+        async move { Ok(body) }
+    }
+}
+
+pub fn query(url: &Url, key: &str) -> String {
+    url.query_pairs()
+        .find(|(k, _)| k == key)
+        .map(|(_, value)| value.into_owned())
+        .unwrap_or_default()
+}
+
+/// 一页记录，按接口返回的顺序从新到旧排列
+pub fn records(gacha_type: &str, ids: &[&str]) -> String {
+    let list = ids
+        .iter()
+        .map(|id| {
+            format!(
+                r#"{{"uid":"100000001","gacha_type":"{gacha_type}","item_id":"","count":"1","time":"2024-01-01 12:00:00","name":"弹弓","lang":"zh-cn","item_type":"武器","rank_type":"3","id":"{id}"}}"#
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    format!(
+        r#"{{"retcode":0,"message":"OK","data":{{"page":"1","size":"5","total":"0","list":[{list}],"region":"cn_gf01"}}}}"#
+    )
+}
+
+pub fn error(retcode: i64, message: &str) -> String {
+    format!(r#"{{"retcode":{retcode},"message":"{message}","data":null}}"#)
+}
+
+/// 不等待的获取选项，重试间隔为 1 毫秒
+pub fn options() -> FetchOptions {
+    FetchOptions::new()
+        .request_interval(Duration::ZERO)
+        .page_interval(Duration::ZERO)
+        .retry(RetryPolicy::new().base_delay(Duration::from_millis(1)))
+}
@@ -0,0 +1,242 @@
+use gacha::{get_gacha_url_with_authkey, Endpoint, GachaUrlBuilder, GameType};
+use url::Url;
+
+const GAME_TYPES: [GameType; 6] = [
+    GameType::Hk4eCN,
+    GameType::Hk4eGlobal,
+    GameType::HkrpgCN,
+    GameType::HkrpgGlobal,
+    GameType::NapCN,
+    GameType::NapGlobal,
+];
+
+/// Base64 中的 `+`、`/` 与 `=` 都需要编码
+const AUTHKEY: &str = "ab+c/d==";
+
+fn query(url: &Url, key: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(k, _)| k == key)
+        .map(|(_, value)| value.into_owned())
+}
+
+fn build(authkey: &str) -> Url {
+    GachaUrlBuilder::new(GameType::Hk4eCN, authkey)
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn raw_authkey() {
+    let url = build(AUTHKEY);
+    assert_eq!(query(&url, "authkey").as_deref(), Some(AUTHKEY));
+    assert!(url.query().unwrap().contains("authkey=ab%2Bc%2Fd%3D%3D"));
+}
+
+#[test]
+fn percent_encoded_authkey() {
+    let url = build("ab%2Bc%2Fd%3D%3D");
+    assert_eq!(query(&url, "authkey").as_deref(), Some(AUTHKEY));
+}
+
+#[test]
+fn plus_in_url_is_not_a_space() {
+    let url = build("https://example.com/log?authkey=ab+c/d==&lang=ja-jp");
+    assert_eq!(query(&url, "authkey").as_deref(), Some(AUTHKEY));
+    assert_eq!(query(&url, "lang").as_deref(), Some("ja-jp"));
+}
+
+#[test]
+fn full_url() {
+    let url = build(
+        " https://webstatic.mihoyo.com/hk4e/event/e20190909gacha-v3/index.html?win_mode=fullscreen&authkey_ver=1&sign_type=2&auth_appid=webview_gacha&authkey=ab%2Bc%2Fd%3D%3D&lang=ja-jp&game_biz=hk4e_cn#/log ",
+    );
+    assert_eq!(query(&url, "authkey").as_deref(), Some(AUTHKEY));
+    assert_eq!(query(&url, "lang").as_deref(), Some("ja-jp"));
+    assert_eq!(query(&url, "win_mode"), None);
+    assert_eq!(query(&url, "auth_appid"), None);
+}
+
+#[test]
+fn partial_url() {
+    let url = build("authkey_ver=3&sign_type=4&authkey=ab%2Bc%2Fd%3D%3D");
+    assert_eq!(query(&url, "authkey").as_deref(), Some(AUTHKEY));
+    assert_eq!(query(&url, "authkey_ver").as_deref(), Some("3"));
+    assert_eq!(query(&url, "sign_type").as_deref(), Some("4"));
+
+    let url = build("?authkey=abc");
+    assert_eq!(query(&url, "authkey").as_deref(), Some("abc"));
+}
+
+#[test]
+fn setters_override_url_values() {
+    let url = GachaUrlBuilder::new(GameType::Hk4eCN, "authkey=abc&lang=ja-jp&authkey_ver=3")
+        .lang("ko-kr")
+        .authkey_ver("1")
+        .build()
+        .unwrap();
+    assert_eq!(query(&url, "lang").as_deref(), Some("ko-kr"));
+    assert_eq!(query(&url, "authkey_ver").as_deref(), Some("1"));
+}
+
+#[test]
+fn missing_authkey_is_an_error() {
+    assert!(GachaUrlBuilder::new(GameType::Hk4eCN, "").build().is_err());
+    assert!(
+        GachaUrlBuilder::new(GameType::Hk4eCN, "https://example.com/log?lang=zh-cn")
+            .build()
+            .is_err()
+    );
+}
+
+#[test]
+fn built_url_for_each_game() {
+    for game_type in GAME_TYPES {
+        let endpoint = Endpoint::of(game_type);
+        let url = get_gacha_url_with_authkey(game_type, "abc").unwrap();
+
+        assert_eq!(url[..url::Position::AfterPath], *endpoint.url);
+        assert_eq!(
+            url.query(),
+            Some(
+                format!(
+                    "authkey_ver=1&sign_type=2&authkey=abc&lang={}&game_biz={}",
+                    endpoint.lang, endpoint.game_biz
+                )
+                .as_str()
+            )
+        );
+        assert_eq!(GameType::from_url(&url).unwrap(), game_type);
+    }
+}
+
+fn from_url(url: &str) -> Result<GameType, Box<dyn std::error::Error>> {
+    GameType::from_url(&Url::parse(url).unwrap())
+}
+
+#[test]
+fn from_url_api_hosts() {
+    for game_type in GAME_TYPES {
+        assert_eq!(from_url(Endpoint::of(game_type).url).unwrap(), game_type);
+    }
+}
+
+#[test]
+fn from_url_webstatic_and_legacy_hosts() {
+    let cases = [
+        (
+            "https://webstatic.mihoyo.com/hk4e/event/e20190909gacha-v3/index.html?authkey=abc&game_biz=hk4e_cn#/log",
+            GameType::Hk4eCN,
+        ),
+        (
+            "https://gs.hoyoverse.com/genshin/event/e20190909gacha-v3/index.html?authkey=abc&game_biz=hk4e_global#/log",
+            GameType::Hk4eGlobal,
+        ),
+        (
+            "https://webstatic.mihoyo.com/hkrpg/event/e20211215gacha-v2/index.html?authkey=abc&game_biz=hkrpg_cn#/log",
+            GameType::HkrpgCN,
+        ),
+        (
+            "https://hk4e-api.mihoyo.com/event/gacha_info/api/getGachaLog?authkey=abc",
+            GameType::Hk4eCN,
+        ),
+        (
+            "https://hk4e-api-os.hoyoverse.com/event/gacha_info/api/getGachaLog?authkey=abc",
+            GameType::Hk4eGlobal,
+        ),
+        (
+            "https://api-takumi.mihoyo.com/common/gacha_record/api/getGachaLog?authkey=abc&game_biz=hkrpg_cn",
+            GameType::HkrpgCN,
+        ),
+        (
+            "https://api-os-takumi.mihoyo.com/common/gacha_record/api/getGachaLog?authkey=abc&game_biz=nap_global",
+            GameType::NapGlobal,
+        ),
+    ];
+    for (url, game_type) in cases {
+        assert_eq!(from_url(url).ok(), Some(game_type), "{url}");
+    }
+}
+
+#[test]
+fn from_url_rejects_contradictions() {
+    for url in [
+        "https://public-operation-hk4e.mihoyo.com/gacha_info/api/getGachaLog?game_biz=hkrpg_cn",
+        "https://public-operation-hk4e.mihoyo.com/gacha_info/api/getGachaLog?game_biz=hk4e_global",
+        "https://public-operation-nap-sg.hoyoverse.com/common/gacha_record/api/getGachaLog?game_biz=nap_cn",
+        "https://public-operation-hkrpg.mihoyo.com/gacha_info/api/getGachaLog",
+        "https://public-operation-hk4e.mihoyo.com/gacha_info/api/getGachaLog?game_biz=bh3_cn",
+    ] {
+        assert!(from_url(url).is_err(), "{url}");
+    }
+}
+
+#[test]
+fn from_url_rejects_ambiguous_urls() {
+    assert!(
+        from_url("https://example.com/common/gacha_record/api/getGachaLog?authkey=abc").is_err()
+    );
+    assert!(from_url("https://example.com/?authkey=abc").is_err());
+}
+
+#[test]
+fn candidates_from_url() {
+    let candidates = |url| GameType::candidates_from_url(&Url::parse(url).unwrap()).unwrap();
+    assert_eq!(candidates("https://example.com/?authkey=abc"), GAME_TYPES);
+    assert_eq!(
+        candidates("https://example.com/?authkey=abc&game_biz=hkrpg_global"),
+        [GameType::HkrpgGlobal]
+    );
+    assert_eq!(
+        candidates(
+            "https://public-operation-hk4e.mihoyo.com/gacha_info/api/getGachaLog?game_biz=hkrpg_cn"
+        ),
+        []
+    );
+    assert!(GameType::candidates_from_url(
+        &Url::parse("https://example.com/?game_biz=bh3_cn").unwrap()
+    )
+    .is_err());
+}
+
+#[test]
+fn region_from_url() {
+    let cases = [
+        (
+            "https://webstatic.mihoyo.com/hk4e/event/e20190909gacha-v3/index.html?authkey=abc&region=cn_qd01&game_biz=hk4e_cn#/log",
+            Some("cn_qd01"),
+        ),
+        (
+            "https://public-operation-hkrpg-sg.hoyoverse.com/common/gacha_record/api/getGachaLog?authkey=abc&region=prod_official_asia",
+            Some("prod_official_asia"),
+        ),
+        // 国服绝区零只有一个服务器
+        (
+            "https://public-operation-nap.mihoyo.com/common/gacha_record/api/getGachaLog?authkey=abc",
+            Some("prod_gf_cn"),
+        ),
+        (
+            "https://public-operation-hk4e.mihoyo.com/gacha_info/api/getGachaLog?authkey=abc",
+            None,
+        ),
+    ];
+    for (url, name) in cases {
+        assert_eq!(
+            GameType::region_from_url(&Url::parse(url).unwrap())
+                .unwrap()
+                .map(|region| region.name),
+            name,
+            "{url}"
+        );
+    }
+
+    for url in [
+        "https://public-operation-hk4e.mihoyo.com/gacha_info/api/getGachaLog?region=os_asia",
+        "https://public-operation-hk4e.mihoyo.com/gacha_info/api/getGachaLog?region=prod_gf_cn",
+        "https://example.com/?region=cn_gf01",
+    ] {
+        assert!(
+            GameType::region_from_url(&Url::parse(url).unwrap()).is_err(),
+            "{url}"
+        );
+    }
+}
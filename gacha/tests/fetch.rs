@@ -0,0 +1,285 @@
+mod common;
+
+use common::{error, options, query, records, FakeTransport};
+use gacha::{
+    get_gacha_log_with_url_with_options, get_uigf_with_url_all_with_options, ApiError,
+    CancellationToken, Cancelled, GachaClient, GachaType, GameType, Transport,
+};
+use std::{
+    error::Error,
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+use uigf::{hk4e, ExportTimestamp, Info};
+use url::Url;
+
+/// 含有请求时会被移除的 `timestamp`
+const URL: &str = "https://public-operation-hk4e.mihoyo.com/gacha_info/api/getGachaLog?authkey_ver=1&sign_type=2&authkey=abc&lang=zh-cn&game_biz=hk4e_cn&timestamp=1";
+
+fn wish(gacha_type: hk4e::GachaType) -> GachaType {
+    GachaType::Hk4e(gacha_type)
+}
+
+#[test]
+fn pages_follow_end_id_chain() {
+    let transport = FakeTransport::default()
+        .page("301", "", records("301", &["15", "14"]))
+        .page("301", "14", records("301", &["13", "12"]))
+        .page("301", "12", records("301", &["9"]));
+    let data = get_gacha_log_with_url_with_options(
+        &transport,
+        wish(hk4e::GachaType::CharacterEventWish),
+        Url::parse(URL).unwrap(),
+        &options().page_size(2),
+    )
+    .unwrap();
+
+    let ids = data.list.iter().map(|r| r.id.as_str()).collect::<Vec<_>>();
+    assert_eq!(ids, ["15", "14", "13", "12", "9"]);
+    assert_eq!(data.size, "5");
+    assert_eq!(
+        transport.requests(),
+        [
+            ("301".into(), "".into()),
+            ("301".into(), "14".into()),
+            ("301".into(), "12".into()),
+            ("301".into(), "9".into()),
+        ]
+    );
+
+    let url = &transport.urls()[0];
+    assert_eq!(query(url, "size"), "2");
+    assert_eq!(query(url, "authkey"), "abc");
+    assert_eq!(query(url, "timestamp"), "");
+}
+
+#[test]
+fn empty_pools_are_skipped() {
+    let transport = FakeTransport::default().page("301", "", records("301", &["15", "14"]));
+    let uigf = get_uigf_with_url_all_with_options(
+        &transport,
+        GameType::Hk4eCN,
+        Url::parse(URL).unwrap(),
+        &options(),
+    )
+    .unwrap();
+
+    let accounts = uigf.hk4e.unwrap();
+    assert_eq!(accounts.len(), 1);
+    assert_eq!(accounts[0].timezone, 8);
+    assert_eq!(accounts[0].list.len(), 2);
+    assert_eq!(
+        transport.requests().len(),
+        hk4e::GachaType::all_variants().len() + 1
+    );
+}
+
+#[test]
+fn exported_info_comes_from_options() {
+    let transport = FakeTransport::default().page("301", "", records("301", &["15"]));
+    let info = Info::builder()
+        .export_app("app")
+        .export_app_version("2.0")
+        .export_timestamp(ExportTimestamp::Integer(1704081600));
+    let uigf = get_uigf_with_url_all_with_options(
+        &transport,
+        GameType::Hk4eCN,
+        Url::parse(URL).unwrap(),
+        &options().info(info.clone()),
+    )
+    .unwrap();
+    assert_eq!(uigf.info, info.build());
+}
+
+/// 两个卡池各有多页，其余卡池为空
+fn paged_transport() -> FakeTransport {
+    FakeTransport::default()
+        .page("301", "", records("301", &["15", "14"]))
+        .page("301", "14", records("301", &["13"]))
+        .page("200", "", records("200", &["12", "11"]))
+        .page("200", "11", records("200", &["10", "9"]))
+}
+
+#[test]
+fn concurrent_results_match_sequential() {
+    let info = Info::builder().export_timestamp(ExportTimestamp::Integer(1704081600));
+    let fetch = |concurrency| {
+        get_uigf_with_url_all_with_options(
+            &paged_transport(),
+            GameType::Hk4eCN,
+            Url::parse(URL).unwrap(),
+            &options()
+                .page_size(2)
+                .concurrency(concurrency)
+                .info(info.clone()),
+        )
+        .unwrap()
+    };
+    let sequential = fetch(1);
+    assert_eq!(sequential.hk4e.as_ref().unwrap()[0].list.len(), 7);
+    for concurrency in [2, 4, 16] {
+        assert_eq!(fetch(concurrency), sequential, "{concurrency}");
+    }
+}
+
+/// 记录每次请求的时间
+struct TimedTransport {
+    inner: FakeTransport,
+    times: Mutex<Vec<Instant>>,
+}
+
+impl Transport for TimedTransport {
+    fn get(&self, url: &Url) -> Result<String, Box<dyn Error>> {
+        self.times.lock().unwrap().push(Instant::now());
+        self.inner.get(url)
+    }
+}
+
+#[test]
+fn request_interval_is_shared_by_concurrent_pools() {
+    let interval = Duration::from_millis(30);
+    let transport = TimedTransport {
+        inner: paged_transport(),
+        times: Mutex::new(Vec::new()),
+    };
+    let start = Instant::now();
+    get_uigf_with_url_all_with_options(
+        &transport,
+        GameType::Hk4eCN,
+        Url::parse(URL).unwrap(),
+        &options()
+            .page_size(2)
+            .concurrency(4)
+            .request_interval(interval),
+    )
+    .unwrap();
+
+    // 各请求预约的时间点依次间隔 `interval`，最后一次请求不早于第一次预约后 (n - 1) 个间隔
+    let times = transport.times.into_inner().unwrap();
+    let requests = transport.inner.requests().len();
+    assert_eq!(times.len(), requests);
+    let last = times.iter().max().unwrap();
+    assert!(*last - start >= interval * (requests as u32 - 1));
+}
+
+#[test]
+fn all_pools_empty_is_an_error() {
+    let transport = FakeTransport::default();
+    let result = get_uigf_with_url_all_with_options(
+        &transport,
+        GameType::Hk4eCN,
+        Url::parse(URL).unwrap(),
+        &options(),
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn api_errors_are_returned() {
+    let transport = FakeTransport::default().page("301", "", error(-101, "authkey timeout"));
+    let error = get_gacha_log_with_url_with_options(
+        &transport,
+        wish(hk4e::GachaType::CharacterEventWish),
+        Url::parse(URL).unwrap(),
+        &options(),
+    )
+    .unwrap_err();
+
+    let error = error.downcast_ref::<ApiError>().unwrap();
+    assert_eq!(error.retcode, -101);
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[test]
+fn failing_pool_fails_the_export() {
+    for (retcode, concurrency) in [(-101, 1), (-101, 4), (-110, 1), (-110, 4)] {
+        let transport = FakeTransport::default()
+            .page("301", "", records("301", &["15", "14"]))
+            .page("200", "", records("200", &["12", "11"]))
+            .page("200", "11", error(retcode, "failed"));
+        let error = get_uigf_with_url_all_with_options(
+            &transport,
+            GameType::Hk4eCN,
+            Url::parse(URL).unwrap(),
+            &options().page_size(2).concurrency(concurrency),
+        )
+        .unwrap_err();
+        let error = error.downcast_ref::<ApiError>().unwrap();
+        assert_eq!(error.retcode, retcode, "{concurrency}");
+    }
+}
+
+#[test]
+fn retryable_retcodes_retry_the_failed_page() {
+    let transport = FakeTransport::default()
+        .page("301", "", records("301", &["15", "14"]))
+        .page("301", "14", error(-110, "visit too frequently"))
+        .page("301", "14", records("301", &["13"]));
+    let data = get_gacha_log_with_url_with_options(
+        &transport,
+        wish(hk4e::GachaType::CharacterEventWish),
+        Url::parse(URL).unwrap(),
+        &options(),
+    )
+    .unwrap();
+
+    assert_eq!(data.list.len(), 3);
+    assert_eq!(
+        transport.requests(),
+        [
+            ("301".into(), "".into()),
+            ("301".into(), "14".into()),
+            ("301".into(), "14".into()),
+            ("301".into(), "13".into()),
+        ]
+    );
+}
+
+#[test]
+fn cancellation_interrupts_request_interval() {
+    let transport = FakeTransport::default().page("301", "", records("301", &["15", "14"]));
+    let token = CancellationToken::new();
+    let options = options()
+        .request_interval(Duration::from_secs(30))
+        .cancellation(token.clone());
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        token.cancel();
+    });
+
+    let start = Instant::now();
+    let error = get_gacha_log_with_url_with_options(
+        &transport,
+        wish(hk4e::GachaType::CharacterEventWish),
+        Url::parse(URL).unwrap(),
+        &options,
+    )
+    .unwrap_err();
+    canceller.join().unwrap();
+
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert!(error.is::<Cancelled>());
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[test]
+fn client_keeps_configured_query_keys() {
+    let client = GachaClient::builder_with_transport(FakeTransport::default())
+        .options(options())
+        .query_keys(["authkey", "timestamp"])
+        .page_size(100)
+        .build();
+    client
+        .get_gacha_log(
+            wish(hk4e::GachaType::CharacterEventWish),
+            Url::parse(URL).unwrap(),
+        )
+        .unwrap();
+
+    let url = &client.transport().urls()[0];
+    assert_eq!(
+        url.query(),
+        Some("authkey=abc&timestamp=1&gacha_type=301&real_gacha_type=301&size=20")
+    );
+}
@@ -0,0 +1,176 @@
+#![cfg(feature = "async")]
+
+mod common;
+
+use common::{records, FakeTransport, HK4E_URL};
+use gacha::{
+    get_gacha_log_with_url_with_options_async, get_uigf_with_url_all_with_options_async,
+    CancellationToken, Cancelled, FetchOptions, GachaClient, GachaType, GameType, ProgressEvent,
+};
+use std::{
+    future::Future,
+    sync::{mpsc, Arc},
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+    time::{Duration, Instant},
+};
+use uigf::hk4e;
+use url::Url;
+
+/// 角色活动祈愿每页返回两条记录，共三页，其他卡池为空
+fn transport() -> FakeTransport {
+    FakeTransport::default()
+        .page("301", "", records("301", &["16", "15"]))
+        .page("301", "15", records("301", &["14", "13"]))
+        .page("301", "13", records("301", &["12", "11"]))
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+        thread::park();
+    }
+}
+
+fn options() -> FetchOptions {
+    common::options().page_size(2)
+}
+
+fn wish() -> GachaType {
+    GachaType::Hk4e(hk4e::GachaType::CharacterEventWish)
+}
+
+#[test]
+fn pages_with_options_and_progress() {
+    let transport = transport();
+    let (sender, receiver) = mpsc::channel();
+    let data = block_on(get_gacha_log_with_url_with_options_async(
+        &transport,
+        wish(),
+        Url::parse(HK4E_URL).unwrap(),
+        &options().progress_sender(sender),
+    ))
+    .unwrap();
+
+    assert_eq!(data.list.len(), 6);
+    let requests = transport.urls();
+    assert_eq!(requests.len(), 4);
+    assert!(requests[0].query().unwrap().contains("size=2"));
+
+    let events = receiver.try_iter().collect::<Vec<_>>();
+    assert_eq!(
+        events.first(),
+        Some(&ProgressEvent::PoolStarted { gacha_type: wish() })
+    );
+    assert_eq!(
+        events.last(),
+        Some(&ProgressEvent::PoolFinished {
+            gacha_type: wish(),
+            count: 6
+        })
+    );
+}
+
+#[test]
+fn cancellation_returns_partial_records() {
+    let token = CancellationToken::new();
+    let options = options()
+        .cancellation(token.clone())
+        .on_progress(move |event| {
+            if matches!(event, ProgressEvent::PageFetched { count, .. } if count > 0) {
+                token.cancel();
+            }
+        });
+    let error = block_on(get_uigf_with_url_all_with_options_async(
+        &transport(),
+        GameType::Hk4eCN,
+        Url::parse(HK4E_URL).unwrap(),
+        &options,
+    ))
+    .unwrap_err();
+
+    let partial = error.downcast::<Cancelled>().unwrap().partial.unwrap();
+    let ids = partial.hk4e.unwrap()[0]
+        .list
+        .iter()
+        .map(|item| item.id.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(ids, ["16", "15"]);
+}
+
+#[test]
+fn cancellation_interrupts_page_interval() {
+    let token = CancellationToken::new();
+    let options = options()
+        .page_interval(Duration::from_secs(30))
+        .cancellation(token.clone());
+    let canceller = thread::spawn(move || {
+        thread::sleep(Duration::from_millis(50));
+        token.cancel();
+    });
+
+    let start = Instant::now();
+    let error = block_on(get_gacha_log_with_url_with_options_async(
+        &transport(),
+        wish(),
+        Url::parse(HK4E_URL).unwrap(),
+        &options,
+    ))
+    .unwrap_err();
+    canceller.join().unwrap();
+
+    assert!(start.elapsed() < Duration::from_secs(10));
+    assert_eq!(
+        error
+            .downcast::<Cancelled>()
+            .unwrap()
+            .partial
+            .unwrap()
+            .hk4e
+            .unwrap()[0]
+            .list
+            .len(),
+        2
+    );
+}
+
+#[test]
+fn client_uses_its_options() {
+    let client = GachaClient::builder_with_transport(transport())
+        .options(options())
+        .lang("en-us")
+        .build();
+    let data = block_on(client.get_gacha_log_async(wish(), Url::parse(HK4E_URL).unwrap())).unwrap();
+    assert_eq!(data.list.len(), 6);
+
+    let requests = client.transport().urls();
+    assert!(requests
+        .iter()
+        .all(|url| url.query().unwrap().contains("lang=en-us")));
+}
+
+#[test]
+fn futures_are_send() {
+    fn assert_send<T: Send>(_: T) {}
+    let transport = transport();
+    let options = options();
+    assert_send(get_uigf_with_url_all_with_options_async(
+        &transport,
+        GameType::Hk4eCN,
+        Url::parse(HK4E_URL).unwrap(),
+        &options,
+    ));
+}
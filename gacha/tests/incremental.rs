@@ -0,0 +1,135 @@
+mod common;
+
+use common::{error, records, FakeTransport, HK4E_URL};
+use gacha::{
+    get_account_with_url_incremental_with_options, get_account_with_url_since_with_options,
+    newest_ids, ApiError, FetchOptions, GachaType,
+};
+use std::collections::HashMap;
+use uigf::{
+    hk4e::{self, Hk4e},
+    GachaAccount, GachaItem,
+};
+use url::Url;
+
+/// 账号中的记录，`(gacha_type, id)`
+fn account(records: &[(&str, &str)]) -> Hk4e {
+    let list = records
+        .iter()
+        .map(|(gacha_type, id)| {
+            let uigf_gacha_type = if *gacha_type == "400" { "301" } else { gacha_type };
+            format!(
+                r#"{{"uigf_gacha_type":"{uigf_gacha_type}","gacha_type":"{gacha_type}","item_id":"","time":"2024-01-01 12:00:00","id":"{id}"}}"#
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(",");
+    serde_json::from_str(&format!(
+        r#"{{"uid":"100000001","timezone":8,"lang":"zh-cn","list":[{list}]}}"#
+    ))
+    .unwrap()
+}
+
+fn options() -> FetchOptions {
+    common::options().page_size(2)
+}
+
+/// 角色活动祈愿的 authkey 已过期
+fn expired() -> FakeTransport {
+    FakeTransport::default().page("301", "", error(-101, "authkey timeout"))
+}
+
+fn ids(account: &Hk4e) -> Vec<&str> {
+    account.list().iter().map(GachaItem::id).collect()
+}
+
+#[test]
+fn newest_ids_per_pool() {
+    let account = account(&[
+        ("301", "9"),
+        ("400", "100"),
+        ("301", "99"),
+        ("200", "5"),
+        ("200", "0202401011200000000"),
+        ("302", "0202401011200000001"),
+    ]);
+    assert_eq!(
+        newest_ids(&account),
+        HashMap::from([
+            // 角色活动祈愿-2 与角色活动祈愿合并计算，按数值比较
+            (
+                GachaType::Hk4e(hk4e::GachaType::CharacterEventWish),
+                "100".to_string()
+            ),
+            (
+                GachaType::Hk4e(hk4e::GachaType::CharacterEventWish2),
+                "100".to_string()
+            ),
+            (
+                GachaType::Hk4e(hk4e::GachaType::NoviceWishes),
+                "5".to_string()
+            ),
+        ])
+    );
+}
+
+#[test]
+fn incremental_stops_at_known_id() {
+    let transport = FakeTransport::default()
+        .page("301", "", records("301", &["15", "14"]))
+        .page("301", "14", records("301", &["13", "12"]))
+        .page("301", "12", records("301", &["11"]));
+    let account = account(&[("301", "11"), ("301", "12")]);
+
+    let merged = get_account_with_url_incremental_with_options(
+        &transport,
+        &account,
+        Url::parse(HK4E_URL).unwrap(),
+        &options(),
+    )
+    .unwrap();
+    assert_eq!(ids(&merged), ["11", "12", "13", "14", "15"]);
+
+    let requests = transport.requests();
+    assert!(requests.contains(&("301".into(), "14".into())));
+    assert!(!requests.contains(&("301".into(), "12".into())));
+}
+
+#[test]
+fn since_without_new_records_is_none() {
+    let transport = FakeTransport::default().page("301", "", records("301", &["12", "11"]));
+    let since = HashMap::from([(
+        GachaType::Hk4e(hk4e::GachaType::CharacterEventWish),
+        "12".to_string(),
+    )]);
+    let account = get_account_with_url_since_with_options::<Hk4e, _>(
+        &transport,
+        &since,
+        Url::parse(HK4E_URL).unwrap(),
+        &options(),
+    )
+    .unwrap();
+    assert!(account.is_none());
+}
+
+#[test]
+fn transport_errors_are_returned() {
+    let account = account(&[("301", "11")]);
+    let error = get_account_with_url_incremental_with_options(
+        &expired(),
+        &account,
+        Url::parse(HK4E_URL).unwrap(),
+        &options(),
+    )
+    .unwrap_err();
+    assert_eq!(error.downcast_ref::<ApiError>().unwrap().retcode, -101);
+
+    let error = get_account_with_url_since_with_options::<Hk4e, _>(
+        &expired(),
+        &HashMap::new(),
+        Url::parse(HK4E_URL).unwrap(),
+        &options().concurrency(4),
+    )
+    .unwrap_err();
+    assert_eq!(error.downcast_ref::<ApiError>().unwrap().retcode, -101);
+}
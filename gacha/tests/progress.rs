@@ -0,0 +1,121 @@
+mod common;
+
+use common::{error, options, records, FakeTransport, HK4E_URL};
+use gacha::{
+    get_gacha_log_with_url_with_options, get_uigf_with_url_all_with_options, GachaType, GameType,
+    ProgressEvent,
+};
+use std::{
+    sync::{mpsc, Arc, Mutex},
+    time::Duration,
+};
+use uigf::hk4e;
+use url::Url;
+
+fn wish() -> GachaType {
+    GachaType::Hk4e(hk4e::GachaType::CharacterEventWish)
+}
+
+fn page_fetched(count: usize) -> ProgressEvent {
+    ProgressEvent::PageFetched {
+        gacha_type: wish(),
+        count,
+        newest_time: (count > 0).then(|| "2024-01-01 12:00:00".into()),
+    }
+}
+
+#[test]
+fn callback_receives_events_in_order() {
+    let transport = FakeTransport::default()
+        .page("301", "", records("301", &["15", "14"]))
+        .page("301", "14", error(-110, "visit too frequently"))
+        .page("301", "14", records("301", &["13"]));
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let options = options().on_progress({
+        let events = events.clone();
+        move |event| events.lock().unwrap().push(event)
+    });
+    get_gacha_log_with_url_with_options(
+        &transport,
+        wish(),
+        Url::parse(HK4E_URL).unwrap(),
+        &options,
+    )
+    .unwrap();
+
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 6);
+    assert_eq!(events[0], ProgressEvent::PoolStarted { gacha_type: wish() });
+    assert_eq!(events[1], page_fetched(2));
+    match &events[2] {
+        ProgressEvent::RetryScheduled {
+            gacha_type,
+            attempt,
+            delay,
+            error,
+        } => {
+            assert_eq!(*gacha_type, wish());
+            assert_eq!(*attempt, 2);
+            assert!(*delay <= Duration::from_millis(1));
+            assert_eq!(error, "visit too frequently (retcode -110)");
+        }
+        event => panic!("unexpected event {event:?}"),
+    }
+    assert_eq!(events[3], page_fetched(1));
+    assert_eq!(events[4], page_fetched(0));
+    assert_eq!(
+        events[5],
+        ProgressEvent::PoolFinished {
+            gacha_type: wish(),
+            count: 3
+        }
+    );
+}
+
+#[test]
+fn sender_receives_every_pool_and_export_finished() {
+    let transport = FakeTransport::default().page("301", "", records("301", &["15", "14"]));
+    let (sender, receiver) = mpsc::channel();
+    get_uigf_with_url_all_with_options(
+        &transport,
+        GameType::Hk4eCN,
+        Url::parse(HK4E_URL).unwrap(),
+        &options().progress_sender(sender),
+    )
+    .unwrap();
+
+    let events = receiver.try_iter().collect::<Vec<_>>();
+    let started = events
+        .iter()
+        .filter(|event| matches!(event, ProgressEvent::PoolStarted { .. }))
+        .count();
+    let finished = events
+        .iter()
+        .filter(|event| matches!(event, ProgressEvent::PoolFinished { .. }))
+        .count();
+    assert_eq!(started, hk4e::GachaType::all_variants().len());
+    assert_eq!(finished, started);
+    assert!(events.contains(&ProgressEvent::PoolFinished {
+        gacha_type: wish(),
+        count: 2
+    }));
+    assert_eq!(
+        events.last(),
+        Some(&ProgressEvent::ExportFinished { count: 2 })
+    );
+}
+
+#[test]
+fn closed_receiver_does_not_stop_fetching() {
+    let transport = FakeTransport::default().page("301", "", records("301", &["15", "14"]));
+    let (sender, receiver) = mpsc::channel();
+    drop(receiver);
+    let data = get_gacha_log_with_url_with_options(
+        &transport,
+        wish(),
+        Url::parse(HK4E_URL).unwrap(),
+        &options().progress_sender(sender),
+    )
+    .unwrap();
+    assert_eq!(data.list.len(), 2);
+}
@@ -15,6 +15,7 @@ pub enum Subcommand {
     Hkrpg(SubCommandHkrpg),
     Nap(SubCommandNap),
     Url(SubCommandUrl),
+    Export(SubCommandExport),
 }
 
 /// export gacha logs from Genshin Impact
@@ -91,6 +92,20 @@ pub struct SubCommandUrl {
     pub game: gacha::GameType,
 }
 
+/// export gacha logs from a URL, detecting the game from it
+#[derive(FromArgs, PartialEq, Debug)]
+#[argh(subcommand, name = "export")]
+pub struct SubCommandExport {
+    /// the URL to export
+    #[argh(positional)]
+    pub url: String,
+
+    /// specify the file path to be exported
+    /// (default: printed to the console)
+    #[argh(positional)]
+    pub output: Option<String>,
+}
+
 pub fn parse_game(value: &str) -> Result<gacha::GameType, String> {
     match value {
         "hk4ecn" => Ok(gacha::GameType::Hk4eCN),
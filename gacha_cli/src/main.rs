@@ -35,6 +35,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         args::Subcommand::Url(cmd) => {
             println!("{}", get_gacha_url(cmd.game)?);
         }
+        args::Subcommand::Export(cmd) => {
+            let uigf = gacha::get_uigf_with_url_all_detected(url::Url::parse(&cmd.url)?)?;
+            write_output(cmd.output, uigf.to_json()?)?;
+        }
     }
     Ok(())
 }
@@ -46,24 +50,32 @@ fn process_command(
     global_game_type: gacha::GameType,
     cn_game_type: gacha::GameType,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let game_type = if global {
+        global_game_type
+    } else {
+        cn_game_type
+    };
     let url = if let Some(s) = url_option {
-        url::Url::parse(&s)?
+        let url = url::Url::parse(&s)?;
+        // 镜像或自定义的链接无法判断游戏，只拒绝明确属于其他游戏或服务器的链接
+        let candidates = gacha::GameType::candidates_from_url(&url)
+            .map_err(|error| format!("Invalid gacha URL: {error}"))?;
+        if !candidates.contains(&game_type) {
+            return Err(match candidates[..] {
+                [detected] => format!("The URL is for {detected:?}, not {game_type:?}"),
+                _ => format!("The URL is not for {game_type:?}"),
+            }
+            .into());
+        }
+        url
     } else {
-        gacha::get_gacha_url(if global {
-            global_game_type
-        } else {
-            cn_game_type
-        })?
+        gacha::get_gacha_url(game_type)?
     };
-    let uigf = gacha::get_uigf_with_url_all(
-        if global {
-            global_game_type
-        } else {
-            cn_game_type
-        },
-        url,
-    )?
-    .to_json()?;
+    let uigf = gacha::get_uigf_with_url_all(game_type, url)?.to_json()?;
+    write_output(output, uigf)
+}
+
+fn write_output(output: Option<String>, uigf: String) -> Result<(), Box<dyn std::error::Error>> {
     match output {
         Some(p) => std::fs::write(p, uigf)?,
         None => println!("{}", uigf),
@@ -0,0 +1,225 @@
+use crate::{is_synthetic_id, Uid, UigfV4};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+};
+
+/// 时间的取整精度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TimePrecision {
+    Minute,
+    Hour,
+    Day,
+}
+
+/// 公开分享档案前隐藏 UID 等信息
+///
+/// UID 会被替换为由盐值计算出的稳定假名，并保留服务器前缀，
+/// 以便仍能根据 UID 判断服务器与时区
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anonymizer {
+    salt: String,
+    id_offset: Option<i64>,
+    time_precision: Option<TimePrecision>,
+}
+
+/// 匿名化前后的对照表，由档案所有者保存，用于还原档案
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub struct AnonymizationMapping {
+    /// 假名与原 UID 的对应关系
+    pub uids: Vec<UidMapping>,
+    /// 记录 ID 的偏移量
+    pub id_offset: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub struct UidMapping {
+    pub original: Uid,
+    pub pseudonym: Uid,
+}
+
+impl Anonymizer {
+    pub fn new(salt: impl Into<String>) -> Self {
+        Self {
+            salt: salt.into(),
+            id_offset: None,
+            time_precision: None,
+        }
+    }
+
+    /// 将所有记录 ID 加上 `offset`，可通过对照表还原
+    ///
+    /// 导入时生成的 ID（见 [`is_synthetic_id`]）保留开头的 `0`，只平移其后的部分
+    pub fn shift_ids(mut self, offset: i64) -> Self {
+        self.id_offset = Some(offset);
+        self
+    }
+
+    /// 将所有记录时间向下取整，无法还原
+    pub fn round_times(mut self, precision: TimePrecision) -> Self {
+        self.time_precision = Some(precision);
+        self
+    }
+
+    pub fn anonymize(
+        &self,
+        uigf: &UigfV4,
+    ) -> Result<(UigfV4, AnonymizationMapping), Box<dyn Error>> {
+        let mut uigf = uigf.clone();
+        // 假名不能与档案中任何账号的原 UID 相同，否则还原时无法区分
+        let originals = uids_mut(&mut uigf)
+            .map(|uid| uid.to_string())
+            .collect::<HashSet<_>>();
+        let mut pseudonyms = HashMap::<String, Uid>::new();
+        let mut mapping = AnonymizationMapping {
+            uids: Vec::new(),
+            id_offset: self.id_offset,
+        };
+
+        for uid in uids_mut(&mut uigf) {
+            let original = uid.to_string();
+            let pseudonym = match pseudonyms.get(&original) {
+                Some(pseudonym) => pseudonym.clone(),
+                None => {
+                    let pseudonym = self.pseudonym(uid, |candidate| {
+                        originals.contains(candidate)
+                            || mapping
+                                .uids
+                                .iter()
+                                .any(|m| m.pseudonym.to_string() == candidate)
+                    })?;
+                    mapping.uids.push(UidMapping {
+                        original: uid.clone(),
+                        pseudonym: pseudonym.clone(),
+                    });
+                    pseudonyms.insert(original, pseudonym.clone());
+                    pseudonym
+                }
+            };
+            *uid = pseudonym;
+        }
+
+        for mut record in uigf.records_mut() {
+            if let Some(offset) = self.id_offset {
+                let id = record.item.id_mut();
+                *id = shift_id(id, offset)?;
+            }
+            if let Some(precision) = self.time_precision {
+                let time = record.item.time_mut();
+                *time = round_time(time, precision);
+            }
+        }
+
+        Ok((uigf, mapping))
+    }
+
+    /// 保留 UID 的服务器前缀，其余位数替换为盐值哈希，与原 UID 或已有假名冲突时重新计算
+    fn pseudonym(&self, uid: &Uid, exists: impl Fn(&str) -> bool) -> Result<Uid, Box<dyn Error>> {
+        let original = uid.to_string();
+        if original.len() < 2 || !original.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("Invalid UID: {original}").into());
+        }
+        let (prefix, rest) = original.split_at(server_prefix_len(&original));
+        let modulus = 10u64.pow(rest.len().min(19) as u32);
+
+        let mut round = 0u32;
+        let pseudonym = loop {
+            let hash = fnv1a(&format!("{}\0{}\0{}", self.salt, original, round));
+            let candidate = format!("{prefix}{:0width$}", hash % modulus, width = rest.len());
+            if !exists(&candidate) {
+                break candidate;
+            }
+            round += 1;
+        };
+
+        Ok(match uid {
+            Uid::Integer(_) => Uid::Integer(pseudonym.parse()?),
+            Uid::String(_) => Uid::String(pseudonym),
+        })
+    }
+}
+
+impl AnonymizationMapping {
+    /// 还原 UID 与记录 ID，取整后的时间无法还原
+    pub fn restore(&self, uigf: &UigfV4) -> Result<UigfV4, Box<dyn Error>> {
+        let mut uigf = uigf.clone();
+        for uid in uids_mut(&mut uigf) {
+            let pseudonym = uid.to_string();
+            if let Some(mapping) = self
+                .uids
+                .iter()
+                .find(|m| m.pseudonym.to_string() == pseudonym)
+            {
+                *uid = mapping.original.clone();
+            }
+        }
+        if let Some(offset) = self.id_offset {
+            for mut record in uigf.records_mut() {
+                let id = record.item.id_mut();
+                *id = shift_id(id, -offset)?;
+            }
+        }
+        Ok(uigf)
+    }
+}
+
+fn uids_mut(uigf: &mut UigfV4) -> impl Iterator<Item = &mut Uid> {
+    let hk4e = uigf
+        .hk4e
+        .iter_mut()
+        .flatten()
+        .map(|account| &mut account.uid);
+    let hkrpg = uigf
+        .hkrpg
+        .iter_mut()
+        .flatten()
+        .map(|account| &mut account.uid);
+    let nap = uigf
+        .nap
+        .iter_mut()
+        .flatten()
+        .map(|account| &mut account.uid);
+    hk4e.chain(hkrpg).chain(nap)
+}
+
+/// 九位及以下的 UID 首位为服务器，十位 UID 的前两位为服务器
+fn server_prefix_len(uid: &str) -> usize {
+    if uid.len() >= 10 {
+        2
+    } else {
+        1
+    }
+}
+
+fn shift_id(id: &str, offset: i64) -> Result<String, Box<dyn Error>> {
+    if id.len() > 1 && is_synthetic_id(id) {
+        return Ok(format!("0{}", shift_id(&id[1..], offset)?));
+    }
+    let id = id.parse::<i128>()? + offset as i128;
+    if id < 0 {
+        return Err("Record ID out of range after shifting".into());
+    }
+    Ok(id.to_string())
+}
+
+/// 时间格式为 `YYYY-MM-DD hh:mm:ss`，格式不符时原样返回
+fn round_time(time: &str, precision: TimePrecision) -> String {
+    if time.len() != 19 || !time.is_ascii() {
+        return time.to_string();
+    }
+    let keep = match precision {
+        TimePrecision::Minute => 16,
+        TimePrecision::Hour => 13,
+        TimePrecision::Day => 10,
+    };
+    format!("{}{}", &time[..keep], &" 00:00:00"[keep - 10..])
+}
+
+/// FNV-1a 64 位哈希，保证不同平台与版本下结果一致
+fn fnv1a(data: &str) -> u64 {
+    data.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
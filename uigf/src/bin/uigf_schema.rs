@@ -0,0 +1,9 @@
+//! 输出 UIGF v4 的 JSON Schema，用法：`cargo run -p uigf --features schema --bin uigf_schema > uigf.schema.json`
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&uigf::UigfV4::json_schema())?
+    );
+    Ok(())
+}
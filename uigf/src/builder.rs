@@ -0,0 +1,123 @@
+use crate::{hk4e::Hk4e, hkrpg::Hkrpg, nap::Nap, ExportTimestamp, Info, UigfV4};
+
+/// 档案信息构建器，未设置的字段与 [`Info::new`] 相同
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct InfoBuilder {
+    export_timestamp: Option<ExportTimestamp>,
+    export_app: Option<String>,
+    export_app_version: Option<String>,
+    version: Option<String>,
+}
+
+impl InfoBuilder {
+    /// 导出档案的 App 名称
+    pub fn export_app(mut self, export_app: impl Into<String>) -> Self {
+        self.export_app = Some(export_app.into());
+        self
+    }
+
+    /// 导出档案的 App 版本
+    pub fn export_app_version(mut self, export_app_version: impl Into<String>) -> Self {
+        self.export_app_version = Some(export_app_version.into());
+        self
+    }
+
+    /// 导出档案的时间戳，默认为当前时间
+    pub fn export_timestamp(mut self, export_timestamp: ExportTimestamp) -> Self {
+        self.export_timestamp = Some(export_timestamp);
+        self
+    }
+
+    /// 导出档案的 UIGF 版本号，如 `v4.0`
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    pub fn build(self) -> Info {
+        let info = Info::new();
+        Info {
+            export_timestamp: self.export_timestamp.unwrap_or(info.export_timestamp),
+            export_app: self.export_app.unwrap_or(info.export_app),
+            export_app_version: self.export_app_version.unwrap_or(info.export_app_version),
+            version: self.version.unwrap_or(info.version),
+        }
+    }
+}
+
+/// 档案构建器
+#[derive(Debug, Clone, Default, PartialEq, Hash)]
+pub struct UigfV4Builder {
+    info: InfoBuilder,
+    hk4e: Option<Vec<Hk4e>>,
+    hkrpg: Option<Vec<Hkrpg>>,
+    nap: Option<Vec<Nap>>,
+}
+
+impl UigfV4Builder {
+    /// 导出档案的 App 名称
+    pub fn export_app(mut self, export_app: impl Into<String>) -> Self {
+        self.info = self.info.export_app(export_app);
+        self
+    }
+
+    /// 导出档案的 App 版本
+    pub fn export_app_version(mut self, export_app_version: impl Into<String>) -> Self {
+        self.info = self.info.export_app_version(export_app_version);
+        self
+    }
+
+    /// 导出档案的时间戳，默认为当前时间
+    pub fn export_timestamp(mut self, export_timestamp: ExportTimestamp) -> Self {
+        self.info = self.info.export_timestamp(export_timestamp);
+        self
+    }
+
+    /// 导出档案的 UIGF 版本号，如 `v4.0`
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.info = self.info.version(version);
+        self
+    }
+
+    pub fn hk4e(mut self, hk4e: Vec<Hk4e>) -> Self {
+        self.hk4e = Some(hk4e);
+        self
+    }
+
+    pub fn hkrpg(mut self, hkrpg: Vec<Hkrpg>) -> Self {
+        self.hkrpg = Some(hkrpg);
+        self
+    }
+
+    pub fn nap(mut self, nap: Vec<Nap>) -> Self {
+        self.nap = Some(nap);
+        self
+    }
+
+    pub fn build(self) -> UigfV4 {
+        UigfV4 {
+            info: self.info.build(),
+            hk4e: self.hk4e,
+            hkrpg: self.hkrpg,
+            nap: self.nap,
+        }
+    }
+}
+
+impl Info {
+    pub fn builder() -> InfoBuilder {
+        InfoBuilder::default()
+    }
+}
+
+impl UigfV4 {
+    pub fn builder() -> UigfV4Builder {
+        UigfV4Builder::default()
+    }
+
+    /// 替换档案信息
+    pub fn with_info(mut self, info: Info) -> Self {
+        self.info = info;
+        self
+    }
+}
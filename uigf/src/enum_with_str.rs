@@ -1,6 +1,7 @@
 #[macro_export]
 macro_rules! enum_with_str {
     ($(#[$enum_meta:meta])* $name:ident { $($(#[$meta:meta])* $variant:ident => $str_val:expr),* $(,)? }) => {
+        #[cfg_attr(feature = "schema", derive(::schemars::JsonSchema))]
         $(#[$enum_meta])*
         #[non_exhaustive]
         #[derive(::serde::Serialize,
@@ -9,6 +10,7 @@ macro_rules! enum_with_str {
                  ::core::marker::Copy,
                  ::core::clone::Clone,
                  ::core::cmp::PartialEq,
+                 ::core::cmp::Eq,
                  ::core::hash::Hash)]
         pub enum $name {
             $(
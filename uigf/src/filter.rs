@@ -0,0 +1,149 @@
+use crate::{cmp_id, GachaAccount, GachaItem, UigfV4};
+use std::{
+    cmp::Ordering,
+    ops::{Bound, RangeBounds},
+};
+
+/// 记录筛选条件
+///
+/// 各条件之间为“且”的关系，同一条件设置多个值时为“或”的关系，未设置的条件不参与筛选
+#[derive(Debug, Clone, PartialEq)]
+pub struct Filter {
+    time: (Bound<String>, Bound<String>),
+    id: (Bound<String>, Bound<String>),
+    pools: Vec<String>,
+    ranks: Vec<String>,
+    names: Vec<String>,
+    item_ids: Vec<String>,
+    gacha_ids: Vec<String>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Filter {
+    pub fn new() -> Self {
+        Self {
+            time: (Bound::Unbounded, Bound::Unbounded),
+            id: (Bound::Unbounded, Bound::Unbounded),
+            pools: Vec::new(),
+            ranks: Vec::new(),
+            names: Vec::new(),
+            item_ids: Vec::new(),
+            gacha_ids: Vec::new(),
+        }
+    }
+
+    /// 按抽取时间筛选，时间格式为 `YYYY-MM-DD hh:mm:ss`，如 `"2024-01-01 00:00:00"..`
+    pub fn time_range<S: AsRef<str>>(mut self, range: impl RangeBounds<S>) -> Self {
+        self.time = to_owned_bounds(range);
+        self
+    }
+
+    /// 按记录 ID 的数值大小筛选
+    pub fn id_range<S: AsRef<str>>(mut self, range: impl RangeBounds<S>) -> Self {
+        self.id = to_owned_bounds(range);
+        self
+    }
+
+    /// 按卡池筛选，与卡池类型或保底分组（原神为 uigf_gacha_type）任一相同即可，如 `"301"`
+    pub fn pool(mut self, pool: impl ToString) -> Self {
+        self.pools.push(pool.to_string());
+        self
+    }
+
+    /// 按物品等级筛选，如 `"5"`
+    pub fn rank(mut self, rank: impl ToString) -> Self {
+        self.ranks.push(rank.to_string());
+        self
+    }
+
+    /// 按物品名称筛选
+    pub fn name(mut self, name: impl ToString) -> Self {
+        self.names.push(name.to_string());
+        self
+    }
+
+    /// 按物品的内部 ID 筛选
+    pub fn item_id(mut self, item_id: impl ToString) -> Self {
+        self.item_ids.push(item_id.to_string());
+        self
+    }
+
+    /// 按卡池 Id 筛选，原神记录没有卡池 Id，设置后不会匹配任何原神记录
+    pub fn gacha_id(mut self, gacha_id: impl ToString) -> Self {
+        self.gacha_ids.push(gacha_id.to_string());
+        self
+    }
+
+    pub fn matches<I: GachaItem>(&self, item: &I) -> bool {
+        in_range(item.time(), &self.time, str::cmp)
+            && in_range(item.id(), &self.id, cmp_id)
+            && (self.pools.is_empty()
+                || self.pools.iter().any(|pool| {
+                    *pool == item.gacha_type().to_string() || *pool == item.pity_group().to_string()
+                }))
+            && any_of(&self.ranks, item.rank_type())
+            && any_of(&self.names, item.name())
+            && any_of(&self.item_ids, Some(item.item_id()))
+            && any_of(&self.gacha_ids, item.gacha_id())
+    }
+}
+
+fn to_owned_bounds<S: AsRef<str>>(range: impl RangeBounds<S>) -> (Bound<String>, Bound<String>) {
+    let to_owned = |bound: Bound<&S>| match bound {
+        Bound::Included(s) => Bound::Included(s.as_ref().to_string()),
+        Bound::Excluded(s) => Bound::Excluded(s.as_ref().to_string()),
+        Bound::Unbounded => Bound::Unbounded,
+    };
+    (to_owned(range.start_bound()), to_owned(range.end_bound()))
+}
+
+fn in_range(
+    value: &str,
+    (start, end): &(Bound<String>, Bound<String>),
+    cmp: fn(&str, &str) -> Ordering,
+) -> bool {
+    let after_start = match start {
+        Bound::Included(start) => cmp(value, start).is_ge(),
+        Bound::Excluded(start) => cmp(value, start).is_gt(),
+        Bound::Unbounded => true,
+    };
+    let before_end = match end {
+        Bound::Included(end) => cmp(value, end).is_le(),
+        Bound::Excluded(end) => cmp(value, end).is_lt(),
+        Bound::Unbounded => true,
+    };
+    after_start && before_end
+}
+
+fn any_of(values: &[String], value: Option<&str>) -> bool {
+    values.is_empty() || value.is_some_and(|value| values.iter().any(|v| v == value))
+}
+
+impl UigfV4 {
+    /// 按条件筛选所有账号的记录，返回结构相同的新档案，账号即使没有匹配的记录也会保留
+    pub fn filter(&self, filter: &Filter) -> Self {
+        fn filter_accounts<A: GachaAccount>(
+            accounts: &Option<Vec<A>>,
+            filter: &Filter,
+        ) -> Option<Vec<A>> {
+            accounts.as_ref().map(|accounts| {
+                accounts
+                    .iter()
+                    .map(|account| account.filter(filter))
+                    .collect()
+            })
+        }
+
+        Self {
+            info: self.info.clone(),
+            hk4e: filter_accounts(&self.hk4e, filter),
+            hkrpg: filter_accounts(&self.hkrpg, filter),
+            nap: filter_accounts(&self.nap, filter),
+        }
+    }
+}
@@ -1,9 +1,10 @@
-use crate::{LanguageCode, Uid};
+use crate::{LanguageCode, Region, Uid};
 use serde::{Deserialize, Serialize};
 
 /// HK4E 原神
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Hk4e {
     /// UID
     pub uid: Uid,
@@ -13,11 +14,15 @@ pub struct Hk4e {
     pub lang: Option<LanguageCode>,
     /// 抽卡记录
     pub list: Vec<Hk4eItem>,
+    /// 获取记录时接口返回的服务器，不属于 UIGF，导入时为 `None`，见 [`GachaAccount::region`](crate::GachaAccount::region)
+    #[serde(skip)]
+    pub region: Option<Region>,
 }
 
 /// 原神抽卡记录
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Hk4eItem {
     /// UIGF 卡池类型，用于区分卡池类型不同，但卡池保底计算相同的物品
     pub uigf_gacha_type: UigfGachaType,
@@ -57,6 +62,7 @@ enum_with_str! {
 
 enum_with_str! {
     /// 卡池类型
+    #[cfg_attr(feature = "schema", schemars(rename = "Hk4eGachaType"))]
     GachaType {
         /// 常驻祈愿
         PermanentWish => "100",
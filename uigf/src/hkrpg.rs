@@ -1,9 +1,10 @@
-use crate::{LanguageCode, Uid};
+use crate::{LanguageCode, Region, Uid};
 use serde::{Deserialize, Serialize};
 
 /// 崩坏：星穹铁道
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Hkrpg {
     /// UID
     pub uid: Uid,
@@ -13,11 +14,15 @@ pub struct Hkrpg {
     pub lang: Option<LanguageCode>,
     /// 抽卡记录
     pub list: Vec<HkrpgItem>,
+    /// 获取记录时接口返回的服务器，不属于 UIGF，导入时为 `None`，见 [`GachaAccount::region`](crate::GachaAccount::region)
+    #[serde(skip)]
+    pub region: Option<Region>,
 }
 
 /// 崩坏：星穹铁道抽卡记录
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct HkrpgItem {
     /// 卡池 Id
     pub gacha_id: String,
@@ -41,6 +46,7 @@ pub struct HkrpgItem {
 
 enum_with_str! {
     /// 卡池类型
+    #[cfg_attr(feature = "schema", schemars(rename = "HkrpgGachaType"))]
     GachaType {
         /// 常驻跃迁
         RegularWarp => "1",
@@ -1,16 +1,37 @@
 #[macro_use]
 mod enum_with_str;
+mod builder;
+mod filter;
+mod records;
+mod region;
+mod serialize;
+mod traits;
 mod utils;
+mod version;
 
+pub mod anonymize;
 pub mod hk4e;
 pub mod hkrpg;
 pub mod nap;
+pub mod paimon_moe;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod star_rail_station;
+pub mod stream;
+pub use builder::*;
+pub use filter::*;
+pub use records::*;
+pub use region::*;
+pub use serialize::*;
+pub use traits::*;
 pub use utils::*;
+pub use version::*;
 
 /// 统一可交换抽卡记录标准 v4.0
 /// h<ttps://uigf.org/standards/uigf.html>
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct UigfV4 {
     pub info: Info,
     pub hk4e: Option<Vec<hk4e::Hk4e>>,
@@ -19,7 +40,8 @@ pub struct UigfV4 {
 }
 
 /// 导出档案的 App 名称
-#[derive(Debug, Clone, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Info {
     /// 导出档案的时间戳，秒级
     pub export_timestamp: ExportTimestamp,
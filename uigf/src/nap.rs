@@ -1,9 +1,10 @@
-use crate::{LanguageCode, Uid};
+use crate::{LanguageCode, Region, Uid};
 use serde::{Deserialize, Serialize};
 
 /// 绝区零
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Nap {
     /// UID
     pub uid: Uid,
@@ -13,11 +14,15 @@ pub struct Nap {
     pub lang: Option<LanguageCode>,
     /// 抽卡记录
     pub list: Vec<NapItem>,
+    /// 获取记录时接口返回的服务器，不属于 UIGF，导入时为 `None`，见 [`GachaAccount::region`](crate::GachaAccount::region)
+    #[serde(skip)]
+    pub region: Option<Region>,
 }
 
 /// 绝区零抽卡记录
 #[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct NapItem {
     /// 卡池 Id
     pub gacha_id: Option<String>,
@@ -41,6 +46,7 @@ pub struct NapItem {
 
 enum_with_str! {
     /// 卡池类型
+    #[cfg_attr(feature = "schema", schemars(rename = "NapGachaType"))]
     GachaType {
         /// 常驻频段
         StableChannel => "1",
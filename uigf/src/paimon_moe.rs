@@ -0,0 +1,280 @@
+use crate::{
+    cmp_id,
+    hk4e::{GachaType, Hk4e, Hk4eItem},
+    LanguageCode,
+};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+/// paimon.moe 导出档案中的抽卡部分
+/// <https://paimon.moe/settings>
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub struct PaimonMoe {
+    /// UID
+    #[serde(rename = "wish-uid")]
+    pub uid: Option<String>,
+    /// 角色活动祈愿
+    #[serde(rename = "wish-counter-character-event")]
+    pub character_event: Option<WishCounter>,
+    /// 武器活动祈愿
+    #[serde(rename = "wish-counter-weapon-event")]
+    pub weapon_event: Option<WishCounter>,
+    /// 常驻祈愿
+    #[serde(rename = "wish-counter-standard")]
+    pub standard: Option<WishCounter>,
+    /// 新手祈愿
+    #[serde(rename = "wish-counter-beginners")]
+    pub beginners: Option<WishCounter>,
+    /// 集录祈愿
+    #[serde(rename = "wish-counter-chronicled")]
+    pub chronicled: Option<WishCounter>,
+}
+
+/// paimon.moe 单个卡池的计数器
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub struct WishCounter {
+    /// 抽卡总数
+    pub total: u32,
+    /// 距上次五星的抽数
+    pub legendary: u32,
+    /// 距上次四星的抽数
+    pub rare: u32,
+    /// 是否大保底
+    pub guaranteed: Option<Guaranteed>,
+    /// 抽卡记录，按时间升序
+    pub pulls: Vec<Pull>,
+}
+
+/// paimon.moe 大保底状态
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Guaranteed {
+    pub legendary: bool,
+    pub rare: bool,
+}
+
+/// paimon.moe 抽卡记录
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Pull {
+    /// 物品类型，`character` 或 `weapon`
+    #[serde(rename = "type")]
+    pub item_type: String,
+    /// 卡池类型，与米哈游 API 的 gacha_type 相同
+    pub code: String,
+    /// paimon.moe 的物品键，如 `hu_tao`
+    pub id: String,
+    /// 抽取物品时的当地时间
+    pub time: String,
+    /// 抽取该物品时的保底计数
+    pub pity: u32,
+    /// 十连分组
+    pub group: Option<u32>,
+    /// 是否为 UP 物品
+    pub rate: Option<bool>,
+}
+
+/// paimon.moe 物品键与 UIGF 物品的对应关系
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub struct ItemMapping {
+    /// paimon.moe 的物品键
+    pub key: String,
+    /// 物品的内部 ID
+    pub item_id: String,
+    /// 物品名称
+    pub name: String,
+    /// 物品类型
+    pub item_type: String,
+    /// 物品等级
+    pub rank_type: String,
+}
+
+/// 物品对照表，paimon.moe 不记录 item_id 与等级，转换时需要由调用方提供
+#[derive(Debug, Clone, Default, PartialEq, Hash, Serialize, Deserialize)]
+pub struct ItemTable {
+    pub items: Vec<ItemMapping>,
+}
+
+impl ItemTable {
+    pub fn new(items: Vec<ItemMapping>) -> Self {
+        Self { items }
+    }
+
+    pub fn by_key(&self, key: &str) -> Option<&ItemMapping> {
+        self.items.iter().find(|item| item.key == key)
+    }
+
+    /// 先按 item_id 查找，找不到时再按名称查找
+    pub fn by_item(&self, item_id: &str, name: Option<&str>) -> Option<&ItemMapping> {
+        self.items
+            .iter()
+            .find(|item| !item_id.is_empty() && item.item_id == item_id)
+            .or_else(|| name.and_then(|name| self.items.iter().find(|item| item.name == name)))
+    }
+}
+
+impl PaimonMoe {
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> Result<String, Box<dyn Error>> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    fn counters(&self) -> impl Iterator<Item = &WishCounter> {
+        [
+            &self.character_event,
+            &self.weapon_event,
+            &self.standard,
+            &self.beginners,
+            &self.chronicled,
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    /// 转换为 UIGF 原神账号
+    ///
+    /// paimon.moe 不保存记录 ID，转换时以 `time` 与同一秒内的序号生成升序的 ID，
+    /// 生成的 ID 以 `0` 开头，见 [`is_synthetic_id`](crate::is_synthetic_id)
+    pub fn to_hk4e(
+        &self,
+        table: &ItemTable,
+        timezone: i64,
+        lang: Option<LanguageCode>,
+    ) -> Result<Hk4e, Box<dyn Error>> {
+        let uid = self.uid.as_deref().ok_or("UID not found")?;
+
+        let mut pulls = self
+            .counters()
+            .flat_map(|counter| counter.pulls.iter())
+            .collect::<Vec<_>>();
+        pulls.sort_by(|a, b| a.time.cmp(&b.time));
+
+        let mut last_time = None;
+        let mut sequence = 0;
+        let list = pulls
+            .into_iter()
+            .map(|pull| {
+                if last_time == Some(&pull.time) {
+                    sequence += 1;
+                } else {
+                    last_time = Some(&pull.time);
+                    sequence = 0;
+                }
+                let item = table
+                    .by_key(&pull.id)
+                    .ok_or_else(|| format!("Unknown item: {}", pull.id))?;
+                let gacha_type = pull.code.parse::<GachaType>()?;
+                Ok(Hk4eItem {
+                    uigf_gacha_type: gacha_type.to_uigf(),
+                    gacha_type,
+                    item_id: item.item_id.clone(),
+                    count: Some("1".into()),
+                    time: pull.time.clone(),
+                    name: Some(item.name.clone()),
+                    item_type: Some(item.item_type.clone()),
+                    rank_type: Some(item.rank_type.clone()),
+                    id: generate_id(&pull.time, sequence)?,
+                })
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+        Ok(Hk4e {
+            uid: uid.parse()?,
+            timezone,
+            lang,
+            region: None,
+            list,
+        })
+    }
+
+    /// 由 UIGF 原神账号生成 paimon.moe 档案，保底计数按 UIGF 卡池类型重新计算
+    pub fn from_hk4e(hk4e: &Hk4e, table: &ItemTable) -> Result<Self, Box<dyn Error>> {
+        let mut items = hk4e.list.iter().collect::<Vec<_>>();
+        items.sort_by(|a, b| cmp_id(&a.id, &b.id));
+
+        let mut paimon = Self {
+            uid: Some(hk4e.uid.to_string()),
+            character_event: None,
+            weapon_event: None,
+            standard: None,
+            beginners: None,
+            chronicled: None,
+        };
+
+        for item in items {
+            let mapping = table
+                .by_item(&item.item_id, item.name.as_deref())
+                .ok_or_else(|| format!("Unknown item: {}", item.item_id))?;
+            let counter = match item.uigf_gacha_type.to_string().as_str() {
+                "100" => &mut paimon.beginners,
+                "200" => &mut paimon.standard,
+                "301" => &mut paimon.character_event,
+                "302" => &mut paimon.weapon_event,
+                "500" => &mut paimon.chronicled,
+                _ => return Err("Unsupported gacha type".into()),
+            }
+            .get_or_insert_with(WishCounter::new);
+            counter.push(item, mapping);
+        }
+
+        Ok(paimon)
+    }
+}
+
+impl WishCounter {
+    fn new() -> Self {
+        Self {
+            total: 0,
+            legendary: 0,
+            rare: 0,
+            guaranteed: None,
+            pulls: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, item: &Hk4eItem, mapping: &ItemMapping) {
+        self.total += 1;
+        self.legendary += 1;
+        self.rare += 1;
+        let pity = match mapping.rank_type.as_str() {
+            "5" => {
+                self.rare = 0;
+                std::mem::replace(&mut self.legendary, 0)
+            }
+            "4" => std::mem::replace(&mut self.rare, 0),
+            _ => 1,
+        };
+        self.pulls.push(Pull {
+            item_type: match mapping.item_type.as_str() {
+                "角色" | "Character" => "character",
+                _ => "weapon",
+            }
+            .into(),
+            code: item.gacha_type.to_string(),
+            id: mapping.key.clone(),
+            time: item.time.clone(),
+            pity,
+            group: None,
+            rate: None,
+        });
+    }
+}
+
+/// 以 `0`、`YYYYMMDDhhmmss` 与同一秒内的四位序号拼接生成 19 位的记录 ID
+fn generate_id(time: &str, sequence: usize) -> Result<String, Box<dyn Error>> {
+    let digits = time
+        .chars()
+        .filter(char::is_ascii_digit)
+        .collect::<String>();
+    if digits.len() != 14 {
+        return Err(format!("Invalid time: {time}").into());
+    }
+    if sequence > 9999 {
+        return Err(format!("Too many pulls at {time}").into());
+    }
+    Ok(format!("0{digits}{sequence:04}"))
+}
@@ -0,0 +1,230 @@
+use crate::{
+    hk4e::{Hk4e, Hk4eItem},
+    hkrpg::{Hkrpg, HkrpgItem},
+    nap::{Nap, NapItem},
+    GachaAccount, GachaItem, Game, Item, LanguageCode, Uid, UigfV4,
+};
+
+/// 档案中的一条记录及其所属账号
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Record<'a> {
+    pub game: Game,
+    pub uid: &'a Uid,
+    pub timezone: i64,
+    pub lang: Option<LanguageCode>,
+    pub item: ItemRef<'a>,
+}
+
+/// 档案中的一条可修改记录及其所属账号
+#[derive(Debug, PartialEq)]
+pub struct RecordMut<'a> {
+    pub game: Game,
+    pub uid: &'a Uid,
+    pub timezone: i64,
+    pub lang: Option<LanguageCode>,
+    pub item: ItemMut<'a>,
+}
+
+/// 从档案中取出的一条记录及其所属账号
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub struct OwnedRecord {
+    pub game: Game,
+    pub uid: Uid,
+    pub timezone: i64,
+    pub lang: Option<LanguageCode>,
+    pub item: Item,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Hash)]
+pub enum ItemRef<'a> {
+    Hk4e(&'a Hk4eItem),
+    Hkrpg(&'a HkrpgItem),
+    Nap(&'a NapItem),
+}
+
+#[derive(Debug, PartialEq, Hash)]
+pub enum ItemMut<'a> {
+    Hk4e(&'a mut Hk4eItem),
+    Hkrpg(&'a mut HkrpgItem),
+    Nap(&'a mut NapItem),
+}
+
+/// 为三种记录枚举生成读取公共字段的方法，字段由各记录的 [`GachaItem`] 实现读取
+macro_rules! item_accessors {
+    ($name:ident $(<$lt:lifetime>)?) => {
+        impl $(<$lt>)? $name $(<$lt>)? {
+            pub fn game(&self) -> Game {
+                dispatch!($name, self, item => item.game())
+            }
+
+            pub fn id(&self) -> &str {
+                dispatch!($name, self, item => item.id())
+            }
+
+            pub fn item_id(&self) -> &str {
+                dispatch!($name, self, item => item.item_id())
+            }
+
+            pub fn time(&self) -> &str {
+                dispatch!($name, self, item => item.time())
+            }
+
+            pub fn name(&self) -> Option<&str> {
+                dispatch!($name, self, item => item.name())
+            }
+
+            pub fn rank_type(&self) -> Option<&str> {
+                dispatch!($name, self, item => item.rank_type())
+            }
+
+            /// 卡池类型，即米哈游 API 返回的 gacha_type
+            pub fn gacha_type(&self) -> String {
+                dispatch!($name, self, item => item.gacha_type().to_string())
+            }
+        }
+    };
+}
+
+/// 对三种记录分别求值同一表达式
+macro_rules! dispatch {
+    ($name:ident, $value:expr, $item:ident => $body:expr) => {
+        match $value {
+            $name::Hk4e($item) => $body,
+            $name::Hkrpg($item) => $body,
+            $name::Nap($item) => $body,
+        }
+    };
+}
+
+/// 由记录类型取得游戏，借助方法调用的自动解引用统一三种枚举中的引用层数
+trait ItemGame {
+    fn game(&self) -> Game;
+}
+
+impl<I: GachaItem> ItemGame for I {
+    fn game(&self) -> Game {
+        I::GAME
+    }
+}
+
+item_accessors!(Item);
+item_accessors!(ItemRef<'a>);
+item_accessors!(ItemMut<'a>);
+
+impl ItemRef<'_> {
+    pub fn cloned(&self) -> Item {
+        match *self {
+            ItemRef::Hk4e(item) => Item::Hk4e(item.clone()),
+            ItemRef::Hkrpg(item) => Item::Hkrpg(item.clone()),
+            ItemRef::Nap(item) => Item::Nap(item.clone()),
+        }
+    }
+}
+
+impl ItemMut<'_> {
+    pub fn id_mut(&mut self) -> &mut String {
+        match self {
+            ItemMut::Hk4e(item) => &mut item.id,
+            ItemMut::Hkrpg(item) => &mut item.id,
+            ItemMut::Nap(item) => &mut item.id,
+        }
+    }
+
+    pub fn time_mut(&mut self) -> &mut String {
+        match self {
+            ItemMut::Hk4e(item) => &mut item.time,
+            ItemMut::Hkrpg(item) => &mut item.time,
+            ItemMut::Nap(item) => &mut item.time,
+        }
+    }
+}
+
+impl Item {
+    pub fn as_ref(&self) -> ItemRef<'_> {
+        match self {
+            Item::Hk4e(item) => ItemRef::Hk4e(item),
+            Item::Hkrpg(item) => ItemRef::Hkrpg(item),
+            Item::Nap(item) => ItemRef::Nap(item),
+        }
+    }
+}
+
+/// 展开某个游戏的账号列表，`$wrap` 将单条记录包装为对应的枚举
+macro_rules! flatten_accounts {
+    ($accounts:expr, $account:ident, $record:ident, $wrap:path, $iter:ident) => {
+        $accounts.$iter().flatten().flat_map(|account| {
+            let $account {
+                uid,
+                timezone,
+                lang,
+                list,
+                ..
+            } = account;
+            let uid = &*uid;
+            list.$iter().map(move |item| $record {
+                game: Game::$account,
+                uid,
+                timezone: *timezone,
+                lang: *lang,
+                item: $wrap(item),
+            })
+        })
+    };
+}
+
+impl UigfV4 {
+    /// 依次遍历原神、星穹铁道、绝区零所有账号的全部记录
+    pub fn records(&self) -> impl Iterator<Item = Record<'_>> {
+        flatten_accounts!(self.hk4e, Hk4e, Record, ItemRef::Hk4e, iter)
+            .chain(flatten_accounts!(
+                self.hkrpg,
+                Hkrpg,
+                Record,
+                ItemRef::Hkrpg,
+                iter
+            ))
+            .chain(flatten_accounts!(self.nap, Nap, Record, ItemRef::Nap, iter))
+    }
+
+    /// 同 [`UigfV4::records`]，但可以修改记录
+    pub fn records_mut(&mut self) -> impl Iterator<Item = RecordMut<'_>> {
+        flatten_accounts!(self.hk4e, Hk4e, RecordMut, ItemMut::Hk4e, iter_mut)
+            .chain(flatten_accounts!(
+                self.hkrpg,
+                Hkrpg,
+                RecordMut,
+                ItemMut::Hkrpg,
+                iter_mut
+            ))
+            .chain(flatten_accounts!(
+                self.nap,
+                Nap,
+                RecordMut,
+                ItemMut::Nap,
+                iter_mut
+            ))
+    }
+
+    /// 同 [`UigfV4::records`]，但会消耗档案并取出记录
+    pub fn into_records(self) -> impl Iterator<Item = OwnedRecord> {
+        into_records(self.hk4e)
+            .chain(into_records(self.hkrpg))
+            .chain(into_records(self.nap))
+    }
+}
+
+fn into_records<A: GachaAccount>(accounts: Option<Vec<A>>) -> impl Iterator<Item = OwnedRecord> {
+    accounts.into_iter().flatten().flat_map(|account| {
+        let header = account.header();
+        account
+            .into_list()
+            .into_iter()
+            .map(move |item| OwnedRecord {
+                game: A::Item::GAME,
+                uid: header.uid.clone(),
+                timezone: header.timezone,
+                lang: header.lang,
+                item: item.into(),
+            })
+    })
+}
@@ -0,0 +1,87 @@
+use crate::Game;
+
+/// 游戏服务器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Region {
+    pub game: Game,
+    /// 服务器名称，与抽卡记录接口返回的 `region` 相同，如 cn_gf01、prod_official_usa
+    pub name: &'static str,
+    /// 服务器时区，抽卡记录中的时间为该时区的当地时间
+    pub timezone: i64,
+    /// 是否为国际服
+    pub global: bool,
+    /// 该服务器的 UID 前缀，原神与崩坏：星穹铁道的 10 位 UID 以第二位数字区分服务器
+    prefixes: &'static [&'static str],
+}
+
+const fn region(
+    game: Game,
+    name: &'static str,
+    timezone: i64,
+    global: bool,
+    prefixes: &'static [&'static str],
+) -> Region {
+    Region {
+        game,
+        name,
+        timezone,
+        global,
+        prefixes,
+    }
+}
+
+/// 各游戏的服务器，服务器变更时只需修改此处
+const REGIONS: &[Region] = &[
+    region(Game::Hk4e, "cn_gf01", 8, false, &["1", "2", "3", "4"]),
+    region(Game::Hk4e, "cn_qd01", 8, false, &["5"]),
+    region(Game::Hk4e, "os_usa", -5, true, &["6"]),
+    region(Game::Hk4e, "os_euro", 1, true, &["7"]),
+    region(Game::Hk4e, "os_asia", 8, true, &["8"]),
+    region(Game::Hk4e, "os_cht", 8, true, &["9"]),
+    region(Game::Hkrpg, "prod_gf_cn", 8, false, &["1", "2", "3", "4"]),
+    region(Game::Hkrpg, "prod_qd_cn", 8, false, &["5"]),
+    region(Game::Hkrpg, "prod_official_usa", -5, true, &["6"]),
+    region(Game::Hkrpg, "prod_official_eur", 1, true, &["7"]),
+    region(Game::Hkrpg, "prod_official_asia", 8, true, &["8"]),
+    region(Game::Hkrpg, "prod_official_cht", 8, true, &["9"]),
+    // 绝区零国服 UID 为 8 位，国际服为 10 位并以前两位区分服务器
+    region(Game::Nap, "prod_gf_cn", 8, false, &[]),
+    region(Game::Nap, "prod_gf_us", -5, true, &["10"]),
+    region(Game::Nap, "prod_gf_eu", 1, true, &["15"]),
+    region(Game::Nap, "prod_gf_jp", 8, true, &["13"]),
+    region(Game::Nap, "prod_gf_sg", 8, true, &["17"]),
+];
+
+impl Region {
+    /// 所有已知的服务器
+    pub fn all() -> &'static [Region] {
+        REGIONS
+    }
+
+    /// 根据抽卡记录接口返回的 `region` 查找服务器
+    pub fn from_name(game: Game, name: &str) -> Option<Region> {
+        REGIONS
+            .iter()
+            .find(|region| region.game == game && region.name == name)
+            .copied()
+    }
+
+    /// 根据 UID 判断服务器，无法识别时返回 `None`
+    pub fn from_uid(game: Game, uid: &str) -> Option<Region> {
+        if uid.is_empty() || !uid.bytes().all(|byte| byte.is_ascii_digit()) {
+            return None;
+        }
+        let prefix = match game {
+            Game::Nap if uid.len() < 10 => {
+                return Self::from_name(game, "prod_gf_cn");
+            }
+            Game::Nap => &uid[..2],
+            _ if uid.len() >= 10 => &uid[1..2],
+            _ => &uid[..1],
+        };
+        REGIONS
+            .iter()
+            .find(|region| region.game == game && region.prefixes.contains(&prefix))
+            .copied()
+    }
+}
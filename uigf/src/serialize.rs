@@ -0,0 +1,220 @@
+use crate::{cmp_id, GachaAccount, GachaItem, UigfV4, UigfVersion};
+use serde::Serialize;
+use serde_json::ser::{CompactFormatter, Formatter, PrettyFormatter};
+use std::{error::Error, io};
+
+/// 序列化选项，便于将档案保存在 git 等版本管理工具中对比差异
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SerializeOptions {
+    indent: Option<usize>,
+    canonical: bool,
+    one_record_per_line: bool,
+    version: Option<UigfVersion>,
+}
+
+impl SerializeOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 以指定的空格数缩进，默认不换行
+    pub fn indent(mut self, indent: usize) -> Self {
+        self.indent = Some(indent);
+        self
+    }
+
+    /// 账号按 uid 排序，记录按 ID 数值排序，使相同内容总是得到相同的输出
+    pub fn canonical(mut self, canonical: bool) -> Self {
+        self.canonical = canonical;
+        self
+    }
+
+    /// 每条记录单独占一行，未设置缩进时缩进 2 个空格
+    pub fn one_record_per_line(mut self, one_record_per_line: bool) -> Self {
+        self.one_record_per_line = one_record_per_line;
+        self
+    }
+
+    /// 按指定的 UIGF 次版本导出，见 [`UigfV4::downgrade`]
+    pub fn version(mut self, version: UigfVersion) -> Self {
+        self.version = Some(version);
+        self
+    }
+}
+
+impl UigfV4 {
+    pub fn to_json_with(&self, options: &SerializeOptions) -> Result<String, Box<dyn Error>> {
+        let mut buffer = Vec::new();
+        self.to_writer_with(&mut buffer, options)?;
+        Ok(String::from_utf8(buffer)?)
+    }
+
+    pub fn to_writer_with(
+        &self,
+        writer: impl io::Write,
+        options: &SerializeOptions,
+    ) -> Result<(), Box<dyn Error>> {
+        if let Some(version) = options.version {
+            let uigf = self.downgrade(version)?;
+            let options = SerializeOptions {
+                version: None,
+                ..options.clone()
+            };
+            return uigf.to_writer_with(writer, &options);
+        }
+        if options.canonical {
+            let mut uigf = self.clone();
+            uigf.canonicalize();
+            return uigf.to_writer_with(writer, &options.clone().canonical(false));
+        }
+
+        let indent = match (options.indent, options.one_record_per_line) {
+            (Some(indent), _) => Some(indent),
+            (None, true) => Some(2),
+            (None, false) => None,
+        }
+        .map(|indent| vec![b' '; indent]);
+
+        match indent {
+            Some(indent) if options.one_record_per_line => serialize(
+                self,
+                writer,
+                LineFormatter {
+                    pretty: PrettyFormatter::with_indent(&indent),
+                    depth: 0,
+                },
+            ),
+            Some(indent) => serialize(self, writer, PrettyFormatter::with_indent(&indent)),
+            None => serialize(self, writer, CompactFormatter),
+        }
+    }
+
+    /// 账号按 uid 排序，记录按 ID 数值排序
+    pub fn canonicalize(&mut self) {
+        fn sort<A: GachaAccount>(accounts: &mut Option<Vec<A>>) {
+            if let Some(accounts) = accounts {
+                accounts.sort_by(|a, b| cmp_id(&a.uid().to_string(), &b.uid().to_string()));
+                for account in accounts {
+                    account.list_mut().sort_by(|a, b| cmp_id(a.id(), b.id()));
+                }
+            }
+        }
+
+        sort(&mut self.hk4e);
+        sort(&mut self.hkrpg);
+        sort(&mut self.nap);
+    }
+}
+
+fn serialize(
+    uigf: &UigfV4,
+    writer: impl io::Write,
+    formatter: impl Formatter,
+) -> Result<(), Box<dyn Error>> {
+    let mut serializer = serde_json::Serializer::with_formatter(writer, formatter);
+    uigf.serialize(&mut serializer)?;
+    Ok(())
+}
+
+/// 记录对象所在的嵌套深度：根对象、游戏数组、账号对象、list 数组
+const RECORD_DEPTH: usize = 4;
+
+/// 记录以外的部分按缩进格式输出，记录本身压缩为一行
+struct LineFormatter<'a> {
+    pretty: PrettyFormatter<'a>,
+    depth: usize,
+}
+
+impl LineFormatter<'_> {
+    fn inside_record(&self) -> bool {
+        self.depth > RECORD_DEPTH
+    }
+}
+
+impl Formatter for LineFormatter<'_> {
+    fn begin_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.depth += 1;
+        if self.inside_record() {
+            CompactFormatter.begin_array(writer)
+        } else {
+            self.pretty.begin_array(writer)
+        }
+    }
+
+    fn end_array<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        let inside_record = self.inside_record();
+        self.depth -= 1;
+        if inside_record {
+            CompactFormatter.end_array(writer)
+        } else {
+            self.pretty.end_array(writer)
+        }
+    }
+
+    fn begin_array_value<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        if self.inside_record() {
+            CompactFormatter.begin_array_value(writer, first)
+        } else {
+            self.pretty.begin_array_value(writer, first)
+        }
+    }
+
+    fn end_array_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        if self.inside_record() {
+            CompactFormatter.end_array_value(writer)
+        } else {
+            self.pretty.end_array_value(writer)
+        }
+    }
+
+    fn begin_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.depth += 1;
+        if self.inside_record() {
+            CompactFormatter.begin_object(writer)
+        } else {
+            self.pretty.begin_object(writer)
+        }
+    }
+
+    fn end_object<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        let inside_record = self.inside_record();
+        self.depth -= 1;
+        if inside_record {
+            CompactFormatter.end_object(writer)
+        } else {
+            self.pretty.end_object(writer)
+        }
+    }
+
+    fn begin_object_key<W: ?Sized + io::Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        if self.inside_record() {
+            CompactFormatter.begin_object_key(writer, first)
+        } else {
+            self.pretty.begin_object_key(writer, first)
+        }
+    }
+
+    fn begin_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        if self.inside_record() {
+            CompactFormatter.begin_object_value(writer)
+        } else {
+            self.pretty.begin_object_value(writer)
+        }
+    }
+
+    fn end_object_value<W: ?Sized + io::Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        if self.inside_record() {
+            CompactFormatter.end_object_value(writer)
+        } else {
+            self.pretty.end_object_value(writer)
+        }
+    }
+}
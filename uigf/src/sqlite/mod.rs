@@ -0,0 +1,30 @@
+pub mod snap_hutao;
+pub mod starward;
+
+use rusqlite::{types::Value, Connection, OpenFlags};
+use std::{error::Error, path::Path};
+
+fn open(path: impl AsRef<Path>) -> Result<Connection, Box<dyn Error>> {
+    Ok(Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?)
+}
+
+/// 数据库中的 ID 等字段可能以 INTEGER 或 TEXT 存储，统一转换为字符串
+fn value_to_string(value: Value) -> Option<String> {
+    match value {
+        Value::Integer(i) => Some(i.to_string()),
+        Value::Real(f) => Some(f.to_string()),
+        Value::Text(s) => Some(s),
+        Value::Null | Value::Blob(_) => None,
+    }
+}
+
+/// 将 `2023-01-01T12:00:00.0000000+08:00` 等格式截取为 `2023-01-01 12:00:00`
+fn normalize_time(time: &str) -> String {
+    time.chars()
+        .take(19)
+        .map(|c| if c == 'T' { ' ' } else { c })
+        .collect()
+}
@@ -0,0 +1,127 @@
+use super::{normalize_time, open, value_to_string};
+use crate::{
+    cmp_id,
+    hk4e::{Hk4e, Hk4eItem},
+};
+use rusqlite::types::Value;
+use std::{error::Error, path::Path};
+
+/// Snap Hutao 数据库中的一条抽卡记录
+struct Row {
+    id: String,
+    gacha_type: String,
+    query_type: Option<String>,
+    item_id: String,
+    time: String,
+}
+
+/// 读取 `gacha_items` 与 `gacha_archives` 中指定 UID 的抽卡记录，按 ID 数值升序排列
+fn query(path: impl AsRef<Path>, uid: &str) -> Result<Vec<Row>, Box<dyn Error>> {
+    let conn = open(path)?;
+    let mut stmt = conn.prepare(
+        "SELECT i.Id, i.GachaType, i.QueryType, i.ItemId, i.Time \
+         FROM gacha_items i JOIN gacha_archives a ON i.ArchiveId = a.InnerId \
+         WHERE a.Uid = ?1",
+    )?;
+    let mut rows = stmt
+        .query_map([uid], |row| {
+            Ok((
+                row.get::<_, Value>(0)?,
+                row.get::<_, Value>(1)?,
+                row.get::<_, Value>(2)?,
+                row.get::<_, Value>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?
+        .map(|row| {
+            let (id, gacha_type, query_type, item_id, time) = row?;
+            Ok(Row {
+                id: value_to_string(id).ok_or("Id not found")?,
+                gacha_type: value_to_string(gacha_type).ok_or("GachaType not found")?,
+                query_type: value_to_string(query_type),
+                item_id: value_to_string(item_id).unwrap_or_default(),
+                time,
+            })
+        })
+        .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+
+    if rows.is_empty() {
+        return Err("No data found".into());
+    }
+    // ID 可能以 TEXT 存储，不能交给 SQLite 按字典序排序
+    rows.sort_by(|a, b| cmp_id(&a.id, &b.id));
+    Ok(rows)
+}
+
+/// 时区取自记录时间的偏移，记录时间不含偏移时为 UTC+8
+fn timezone(rows: &[Row]) -> Result<i64, Box<dyn Error>> {
+    Ok(rows
+        .iter()
+        .find_map(|row| parse_offset(&row.time).transpose())
+        .transpose()?
+        .unwrap_or(8))
+}
+
+/// 从 Snap Hutao 的 `Userdata.db` 中读取指定 UID 的原神抽卡记录
+///
+/// Snap Hutao 只支持原神，只保存物品 ID，不保存名称、类型与等级，时区取自记录时间的偏移
+pub fn import_hk4e(path: impl AsRef<Path>, uid: &str) -> Result<Hk4e, Box<dyn Error>> {
+    let rows = query(path, uid)?;
+    Ok(Hk4e {
+        uid: uid.parse()?,
+        timezone: timezone(&rows)?,
+        lang: None,
+        region: None,
+        list: rows
+            .into_iter()
+            .map(|row| {
+                let gacha_type = row.gacha_type.parse::<crate::hk4e::GachaType>()?;
+                Ok(Hk4eItem {
+                    uigf_gacha_type: match row.query_type {
+                        Some(query_type) => query_type.parse()?,
+                        None => gacha_type.to_uigf(),
+                    },
+                    gacha_type,
+                    item_id: row.item_id,
+                    count: Some("1".into()),
+                    time: normalize_time(&row.time),
+                    name: None,
+                    item_type: None,
+                    rank_type: None,
+                    id: row.id,
+                })
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?,
+    })
+}
+
+/// 解析记录时间末尾 `+08:00`、`-0500` 或 `Z` 形式的时区偏移，不含偏移时返回 `None`
+///
+/// UIGF 的时区以小时为单位，偏移不是整小时时返回错误
+fn parse_offset(time: &str) -> Result<Option<i64>, Box<dyn Error>> {
+    let Some(suffix) = time.get(19..).filter(|suffix| !suffix.is_empty()) else {
+        return Ok(None);
+    };
+    if suffix.ends_with('Z') {
+        return Ok(Some(0));
+    }
+    let Some(start) = suffix.rfind(['+', '-']) else {
+        return Ok(None);
+    };
+    let offset = &suffix[start + 1..];
+    let (hours, minutes) = match offset.split_once(':') {
+        Some(offset) => offset,
+        None if offset.len() == 4 => offset.split_at(2),
+        None => (offset, "00"),
+    };
+    let hours = hours.parse::<i64>()?;
+    let minutes = minutes.parse::<i64>()?;
+    if minutes != 0 {
+        return Err(format!("Unsupported timezone offset: {}", &suffix[start..]).into());
+    }
+    Ok(Some(if suffix[start..].starts_with('-') {
+        -hours
+    } else {
+        hours
+    }))
+}
@@ -0,0 +1,157 @@
+use super::{normalize_time, open, value_to_string};
+use crate::{
+    cmp_id,
+    hk4e::{Hk4e, Hk4eItem},
+    hkrpg::{Hkrpg, HkrpgItem},
+    nap::{Nap, NapItem},
+    LanguageCode,
+};
+use rusqlite::{types::Value, Connection};
+use std::{error::Error, path::Path};
+
+/// Starward 数据库中的一条抽卡记录
+struct Row {
+    id: String,
+    gacha_id: Option<String>,
+    gacha_type: String,
+    item_id: String,
+    count: Option<String>,
+    time: String,
+    name: Option<String>,
+    item_type: Option<String>,
+    rank_type: Option<String>,
+    lang: Option<String>,
+}
+
+fn query(
+    conn: &Connection,
+    table: &str,
+    gacha_id: &str,
+    uid: &str,
+) -> Result<Vec<Row>, Box<dyn Error>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT Id, {gacha_id}, GachaType, ItemId, Count, Time, Name, ItemType, RankType, Lang \
+         FROM {table} WHERE Uid = ?1"
+    ))?;
+    let mut rows = stmt
+        .query_map([uid], |row| {
+            Ok(Row {
+                id: value_to_string(row.get::<_, Value>(0)?).unwrap_or_default(),
+                gacha_id: value_to_string(row.get::<_, Value>(1)?),
+                gacha_type: value_to_string(row.get::<_, Value>(2)?).unwrap_or_default(),
+                item_id: value_to_string(row.get::<_, Value>(3)?).unwrap_or_default(),
+                count: value_to_string(row.get::<_, Value>(4)?),
+                time: row.get::<_, String>(5)?,
+                name: value_to_string(row.get::<_, Value>(6)?),
+                item_type: value_to_string(row.get::<_, Value>(7)?),
+                rank_type: value_to_string(row.get::<_, Value>(8)?),
+                lang: value_to_string(row.get::<_, Value>(9)?),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if rows.is_empty() {
+        return Err("No data found".into());
+    }
+    // ID 可能以 TEXT 存储，不能交给 SQLite 按字典序排序
+    rows.sort_by(|a, b| cmp_id(&a.id, &b.id));
+    Ok(rows)
+}
+
+fn lang(rows: &[Row]) -> Option<LanguageCode> {
+    rows.iter()
+        .find_map(|row| row.lang.as_deref())
+        .and_then(|lang| lang.to_lowercase().parse().ok())
+}
+
+/// 从 Starward 数据库中读取指定 UID 的原神抽卡记录
+///
+/// Starward 不保存时区，需要由调用方根据服务器提供
+pub fn import_hk4e(
+    path: impl AsRef<Path>,
+    uid: &str,
+    timezone: i64,
+) -> Result<Hk4e, Box<dyn Error>> {
+    let rows = query(&open(path)?, "GenshinGachaItem", "NULL", uid)?;
+    Ok(Hk4e {
+        uid: uid.parse()?,
+        timezone,
+        lang: lang(&rows),
+        region: None,
+        list: rows
+            .into_iter()
+            .map(|row| {
+                let gacha_type = row.gacha_type.parse::<crate::hk4e::GachaType>()?;
+                Ok(Hk4eItem {
+                    uigf_gacha_type: gacha_type.to_uigf(),
+                    gacha_type,
+                    item_id: row.item_id,
+                    count: row.count,
+                    time: normalize_time(&row.time),
+                    name: row.name,
+                    item_type: row.item_type,
+                    rank_type: row.rank_type,
+                    id: row.id,
+                })
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?,
+    })
+}
+
+/// 从 Starward 数据库中读取指定 UID 的星穹铁道跃迁记录
+pub fn import_hkrpg(
+    path: impl AsRef<Path>,
+    uid: &str,
+    timezone: i64,
+) -> Result<Hkrpg, Box<dyn Error>> {
+    let rows = query(&open(path)?, "StarRailGachaItem", "GachaId", uid)?;
+    Ok(Hkrpg {
+        uid: uid.parse()?,
+        timezone,
+        lang: lang(&rows),
+        region: None,
+        list: rows
+            .into_iter()
+            .map(|row| {
+                Ok(HkrpgItem {
+                    gacha_id: row.gacha_id.ok_or("GachaId not found")?,
+                    gacha_type: row.gacha_type.parse()?,
+                    item_id: row.item_id,
+                    count: row.count,
+                    time: normalize_time(&row.time),
+                    name: row.name,
+                    item_type: row.item_type,
+                    rank_type: row.rank_type,
+                    id: row.id,
+                })
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?,
+    })
+}
+
+/// 从 Starward 数据库中读取指定 UID 的绝区零调频记录
+pub fn import_nap(path: impl AsRef<Path>, uid: &str, timezone: i64) -> Result<Nap, Box<dyn Error>> {
+    let rows = query(&open(path)?, "ZZZGachaItem", "GachaId", uid)?;
+    Ok(Nap {
+        uid: uid.parse()?,
+        timezone,
+        lang: lang(&rows),
+        region: None,
+        list: rows
+            .into_iter()
+            .map(|row| {
+                Ok(NapItem {
+                    gacha_id: row.gacha_id,
+                    gacha_type: row.gacha_type.parse()?,
+                    item_id: row.item_id,
+                    count: row.count,
+                    time: normalize_time(&row.time),
+                    name: row.name,
+                    item_type: row.item_type,
+                    rank_type: row.rank_type,
+                    id: row.id,
+                })
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?,
+    })
+}
@@ -0,0 +1,144 @@
+use crate::{
+    cmp_id,
+    hkrpg::{GachaType, Hkrpg, HkrpgItem},
+    LanguageCode,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, error::Error};
+
+/// StarRailStation 等星穹铁道跃迁记录工具的导出格式，记录按卡池键分组
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub struct StarRailStation {
+    /// UID
+    pub uid: String,
+    /// 时区偏移
+    pub region_time_zone: Option<i64>,
+    /// 语言代码
+    pub lang: Option<LanguageCode>,
+    /// 卡池键到跃迁记录的映射，如 `character`、`light_cone`
+    pub pools: BTreeMap<String, Vec<Warp>>,
+}
+
+/// 跃迁记录
+#[serde_with::skip_serializing_none]
+#[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Warp {
+    /// 记录内部 ID
+    pub id: String,
+    /// 卡池 Id
+    pub gacha_id: String,
+    /// 物品的内部 ID
+    pub item_id: String,
+    /// 抽取物品时对应时区下的当地时间
+    pub time: String,
+    /// 物品名称
+    pub name: Option<String>,
+    /// 物品类型
+    pub item_type: Option<String>,
+    /// 物品等级
+    pub rank_type: Option<String>,
+    /// 物品个数
+    pub count: Option<String>,
+}
+
+/// 由卡池键还原卡池类型，同时接受各工具常见的别名
+pub fn gacha_type_by_pool_key(key: &str) -> Result<GachaType, Box<dyn Error>> {
+    match key {
+        "regular" | "standard" | "stellar" | "1" => Ok(GachaType::RegularWarp),
+        "starter" | "beginner" | "departure" | "2" => Ok(GachaType::StarterWarp),
+        "character" | "character_event" | "character-event" | "11" => {
+            Ok(GachaType::CharacterEventWarp)
+        }
+        "light_cone" | "lightcone" | "light_cone_event" | "light-cone-event" | "12" => {
+            Ok(GachaType::LightConeEventWarp)
+        }
+        _ => Err(format!("Unknown pool key: {key}").into()),
+    }
+}
+
+/// 卡池类型对应的卡池键
+pub fn pool_key_by_gacha_type(gacha_type: GachaType) -> &'static str {
+    match gacha_type {
+        GachaType::RegularWarp => "regular",
+        GachaType::StarterWarp => "starter",
+        GachaType::CharacterEventWarp => "character",
+        GachaType::LightConeEventWarp => "light_cone",
+    }
+}
+
+impl StarRailStation {
+    pub fn from_json(json: &str) -> Result<Self, Box<dyn Error>> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    pub fn to_json(&self) -> Result<String, Box<dyn Error>> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// 转换为 UIGF 星穹铁道账号，文件中缺少时区时使用 `default_timezone`
+    ///
+    /// 与已有账号合并使用 [`GachaAccount::merge`](crate::GachaAccount::merge)
+    pub fn to_hkrpg(&self, default_timezone: i64) -> Result<Hkrpg, Box<dyn Error>> {
+        let mut list = self
+            .pools
+            .iter()
+            .map(|(key, warps)| {
+                let gacha_type = gacha_type_by_pool_key(key)?;
+                Ok(warps.iter().map(move |warp| HkrpgItem {
+                    gacha_id: warp.gacha_id.clone(),
+                    gacha_type,
+                    item_id: warp.item_id.clone(),
+                    count: warp.count.clone(),
+                    time: warp.time.clone(),
+                    name: warp.name.clone(),
+                    item_type: warp.item_type.clone(),
+                    rank_type: warp.rank_type.clone(),
+                    id: warp.id.clone(),
+                }))
+            })
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>();
+        list.sort_by(|a, b| cmp_id(&a.id, &b.id));
+
+        Ok(Hkrpg {
+            uid: self.uid.parse()?,
+            timezone: self.region_time_zone.unwrap_or(default_timezone),
+            lang: self.lang,
+            region: None,
+            list,
+        })
+    }
+
+    /// 由 UIGF 星穹铁道账号生成，每个卡池内的记录按 ID 升序排列
+    pub fn from_hkrpg(hkrpg: &Hkrpg) -> Self {
+        let mut pools = BTreeMap::<String, Vec<Warp>>::new();
+        for item in &hkrpg.list {
+            pools
+                .entry(pool_key_by_gacha_type(item.gacha_type).into())
+                .or_default()
+                .push(Warp {
+                    id: item.id.clone(),
+                    gacha_id: item.gacha_id.clone(),
+                    item_id: item.item_id.clone(),
+                    time: item.time.clone(),
+                    name: item.name.clone(),
+                    item_type: item.item_type.clone(),
+                    rank_type: item.rank_type.clone(),
+                    count: item.count.clone(),
+                });
+        }
+        pools
+            .values_mut()
+            .for_each(|warps| warps.sort_by(|a, b| cmp_id(&a.id, &b.id)));
+
+        Self {
+            uid: hkrpg.uid.to_string(),
+            region_time_zone: Some(hkrpg.timezone),
+            lang: hkrpg.lang,
+            pools,
+        }
+    }
+}
@@ -0,0 +1,375 @@
+//! 流式读写 UIGF 档案，适用于无法一次性载入内存的大文件
+
+use crate::{
+    hk4e::Hk4eItem, hkrpg::HkrpgItem, nap::NapItem, AccountHeader, GachaItem, Game, Info, Item,
+    LanguageCode, Uid, UigfVersion,
+};
+use serde::{
+    de::{self, DeserializeOwned, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor},
+    Deserializer,
+};
+use std::{
+    error::Error,
+    fmt::{self, Formatter},
+    io::{Read, Write},
+    marker::PhantomData,
+};
+
+/// 流式读取时产生的事件
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event<'a> {
+    /// 档案信息
+    Info(Info),
+    /// 开始读取某个账号的抽卡记录
+    Account(Game, &'a AccountHeader),
+    /// 一条抽卡记录及其所属账号
+    Item(&'a AccountHeader, Item),
+}
+
+/// 流式读取 UIGF 档案，每解析出一条记录便调用一次 `callback`
+///
+/// 账号的 `uid` 与 `timezone` 需出现在 `list` 之前才能边解析边回调，
+/// 否则该账号的记录会先缓存，待账号解析完毕后再依次回调；
+/// 主版本号不受支持的档案会返回错误
+pub fn read<R, F>(reader: R, mut callback: F) -> Result<(), Box<dyn Error>>
+where
+    R: Read,
+    F: FnMut(Event<'_>) -> Result<(), Box<dyn Error>>,
+{
+    let mut context = Context {
+        callback: &mut callback,
+        error: None,
+    };
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let result = RootSeed(&mut context).deserialize(&mut deserializer);
+    if let Some(error) = context.error {
+        return Err(error);
+    }
+    result?;
+    deserializer.end()?;
+    Ok(())
+}
+
+type Callback<'f> = dyn FnMut(Event<'_>) -> Result<(), Box<dyn Error>> + 'f;
+
+struct Context<'f> {
+    callback: &'f mut Callback<'f>,
+    /// 回调返回的错误，保存下来以便原样返回给调用方
+    error: Option<Box<dyn Error>>,
+}
+
+impl Context<'_> {
+    fn emit<E: de::Error>(&mut self, event: Event<'_>) -> Result<(), E> {
+        (self.callback)(event).map_err(|error| self.abort(error))
+    }
+
+    /// 保存错误并中止解析
+    fn abort<E: de::Error>(&mut self, error: Box<dyn Error>) -> E {
+        self.error = Some(error);
+        E::custom("aborted")
+    }
+}
+
+struct RootSeed<'c, 'f>(&'c mut Context<'f>);
+
+impl<'de> DeserializeSeed<'de> for RootSeed<'_, '_> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for RootSeed<'_, '_> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("a UIGF v4 object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "info" => {
+                    let info = map.next_value::<Info>()?;
+                    let version = info.uigf_version().and_then(UigfVersion::ensure_supported);
+                    if let Err(error) = version {
+                        return Err(self.0.abort(error));
+                    }
+                    self.0.emit(Event::Info(info))?;
+                }
+                "hk4e" => map.next_value_seed(AccountsSeed::<Hk4eItem>::new(self.0))?,
+                "hkrpg" => map.next_value_seed(AccountsSeed::<HkrpgItem>::new(self.0))?,
+                "nap" => map.next_value_seed(AccountsSeed::<NapItem>::new(self.0))?,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 可以在流式读取中出现的抽卡记录类型
+trait StreamItem: GachaItem + DeserializeOwned {}
+
+impl<T: GachaItem + DeserializeOwned> StreamItem for T {}
+
+struct AccountsSeed<'c, 'f, T> {
+    context: &'c mut Context<'f>,
+    item: PhantomData<T>,
+}
+
+impl<'c, 'f, T> AccountsSeed<'c, 'f, T> {
+    fn new(context: &'c mut Context<'f>) -> Self {
+        Self {
+            context,
+            item: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: StreamItem> DeserializeSeed<'de> for AccountsSeed<'_, '_, T> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_option(self)
+    }
+}
+
+impl<'de, T: StreamItem> Visitor<'de> for AccountsSeed<'_, '_, T> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("a list of accounts")
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<(), E> {
+        Ok(())
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while seq
+            .next_element_seed(AccountSeed::<T>::new(self.context))?
+            .is_some()
+        {}
+        Ok(())
+    }
+}
+
+struct AccountSeed<'c, 'f, T> {
+    context: &'c mut Context<'f>,
+    item: PhantomData<T>,
+}
+
+impl<'c, 'f, T> AccountSeed<'c, 'f, T> {
+    fn new(context: &'c mut Context<'f>) -> Self {
+        Self {
+            context,
+            item: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: StreamItem> DeserializeSeed<'de> for AccountSeed<'_, '_, T> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de, T: StreamItem> Visitor<'de> for AccountSeed<'_, '_, T> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("an account object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<(), A::Error> {
+        let mut uid = None::<Uid>;
+        let mut timezone = None::<i64>;
+        let mut lang = None::<LanguageCode>;
+        let mut header = None::<AccountHeader>;
+        let mut buffered = Vec::<T>::new();
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "uid" => uid = Some(map.next_value()?),
+                "timezone" => timezone = Some(map.next_value()?),
+                "lang" => lang = map.next_value()?,
+                "list" => {
+                    if let (Some(uid), Some(timezone)) = (&uid, timezone) {
+                        let account = header.insert(AccountHeader {
+                            uid: uid.clone(),
+                            timezone,
+                            lang,
+                            region: None,
+                        });
+                        self.context.emit(Event::Account(T::GAME, &*account))?;
+                        map.next_value_seed(ListSeed::<T> {
+                            context: self.context,
+                            header: account,
+                            item: PhantomData,
+                        })?;
+                    } else {
+                        buffered = map.next_value()?;
+                    }
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        if header.is_none() {
+            let account = AccountHeader {
+                uid: uid.ok_or_else(|| de::Error::missing_field("uid"))?,
+                timezone: timezone.ok_or_else(|| de::Error::missing_field("timezone"))?,
+                lang,
+                region: None,
+            };
+            self.context.emit(Event::Account(T::GAME, &account))?;
+            for item in buffered {
+                self.context.emit(Event::Item(&account, item.into()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct ListSeed<'c, 'f, 'h, T> {
+    context: &'c mut Context<'f>,
+    header: &'h AccountHeader,
+    item: PhantomData<T>,
+}
+
+impl<'de, T: StreamItem> DeserializeSeed<'de> for ListSeed<'_, '_, '_, T> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T: StreamItem> Visitor<'de> for ListSeed<'_, '_, '_, T> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str("a list of gacha records")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(item) = seq.next_element::<T>()? {
+            self.context.emit(Event::Item(self.header, item.into()))?;
+        }
+        Ok(())
+    }
+}
+
+/// 流式写入 UIGF 档案
+///
+/// 同一游戏的账号需连续写入，账号写入后再逐条写入该账号的记录
+pub struct Writer<W: Write> {
+    writer: W,
+    state: State,
+    written: Vec<Game>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Root,
+    Section(Game),
+    Account(Game, bool),
+}
+
+impl<W: Write> Writer<W> {
+    pub fn new(mut writer: W, info: &Info) -> Result<Self, Box<dyn Error>> {
+        writer.write_all(b"{\"info\":")?;
+        serde_json::to_writer(&mut writer, info)?;
+        Ok(Self {
+            writer,
+            state: State::Root,
+            written: Vec::new(),
+        })
+    }
+
+    /// 开始写入一个账号，之后写入的记录均属于该账号
+    pub fn begin_account(
+        &mut self,
+        game: Game,
+        header: &AccountHeader,
+    ) -> Result<(), Box<dyn Error>> {
+        self.end_account()?;
+        match self.state {
+            State::Section(current) if current == game => self.writer.write_all(b",")?,
+            state => {
+                if let State::Section(_) = state {
+                    self.writer.write_all(b"]")?;
+                }
+                if self.written.contains(&game) {
+                    return Err(format!("{game} accounts must be written contiguously").into());
+                }
+                self.written.push(game);
+                write!(self.writer, ",\"{game}\":[")?;
+            }
+        }
+
+        self.writer.write_all(b"{\"uid\":")?;
+        serde_json::to_writer(&mut self.writer, &header.uid)?;
+        write!(self.writer, ",\"timezone\":{}", header.timezone)?;
+        if let Some(lang) = header.lang {
+            write!(self.writer, ",\"lang\":\"{lang}\"")?;
+        }
+        self.writer.write_all(b",\"list\":[")?;
+        self.state = State::Account(game, false);
+        Ok(())
+    }
+
+    /// 写入一条属于当前账号的记录
+    pub fn write_item(&mut self, item: &Item) -> Result<(), Box<dyn Error>> {
+        let State::Account(game, has_item) = self.state else {
+            return Err("No account started".into());
+        };
+        if item.game() != game {
+            return Err(format!("Cannot write {} record to {game} account", item.game()).into());
+        }
+        if has_item {
+            self.writer.write_all(b",")?;
+        }
+        match item {
+            Item::Hk4e(item) => serde_json::to_writer(&mut self.writer, item)?,
+            Item::Hkrpg(item) => serde_json::to_writer(&mut self.writer, item)?,
+            Item::Nap(item) => serde_json::to_writer(&mut self.writer, item)?,
+        }
+        self.state = State::Account(game, true);
+        Ok(())
+    }
+
+    /// 结束写入，返回底层的 writer
+    pub fn finish(mut self) -> Result<W, Box<dyn Error>> {
+        self.end_account()?;
+        if let State::Section(_) = self.state {
+            self.writer.write_all(b"]")?;
+        }
+        self.writer.write_all(b"}")?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn end_account(&mut self) -> Result<(), Box<dyn Error>> {
+        if let State::Account(game, _) = self.state {
+            self.writer.write_all(b"]}")?;
+            self.state = State::Section(game);
+        }
+        Ok(())
+    }
+}
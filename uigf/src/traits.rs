@@ -0,0 +1,340 @@
+use crate::{
+    cmp_id,
+    hk4e::{self, Hk4e, Hk4eItem},
+    hkrpg::{self, Hkrpg, HkrpgItem},
+    is_synthetic_id,
+    nap::{self, Nap, NapItem},
+    AccountHeader, Filter, Game, Item, LanguageCode, Region, Uid, UigfV4,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt::Display,
+    hash::Hash,
+};
+
+/// 各游戏抽卡记录的公共字段
+pub trait GachaItem: Clone + Into<Item> {
+    /// 卡池类型
+    type GachaType: Copy + Eq + Hash + Display + 'static;
+    /// 保底计算分组，卡池类型不同但共享保底的记录属于同一分组
+    type PityGroup: Copy + Eq + Hash + Display + 'static;
+
+    const GAME: Game;
+
+    /// 所有卡池类型
+    fn gacha_types() -> &'static [Self::GachaType];
+
+    /// 记录内部 ID
+    fn id(&self) -> &str;
+    /// 物品的内部 ID
+    fn item_id(&self) -> &str;
+    /// 抽取物品时的当地时间
+    fn time(&self) -> &str;
+    /// 物品等级
+    fn rank_type(&self) -> Option<&str>;
+    /// 物品名称
+    fn name(&self) -> Option<&str>;
+    /// 卡池 Id，原神记录没有该字段
+    fn gacha_id(&self) -> Option<&str>;
+    /// 卡池类型
+    fn gacha_type(&self) -> Self::GachaType;
+    /// 保底计算分组
+    fn pity_group(&self) -> Self::PityGroup;
+}
+
+/// 各游戏账号的公共字段
+pub trait GachaAccount: Sized + Clone {
+    type Item: GachaItem;
+
+    fn new(header: AccountHeader, list: Vec<Self::Item>) -> Self;
+
+    fn uid(&self) -> &Uid;
+    fn timezone(&self) -> i64;
+    fn lang(&self) -> Option<LanguageCode>;
+    fn list(&self) -> &[Self::Item];
+    fn list_mut(&mut self) -> &mut Vec<Self::Item>;
+    fn into_list(self) -> Vec<Self::Item>;
+
+    /// 从档案中取出该游戏的账号
+    fn accounts(uigf: &UigfV4) -> Option<&Vec<Self>>;
+    fn accounts_mut(uigf: &mut UigfV4) -> &mut Option<Vec<Self>>;
+    /// 以账号生成只包含该游戏的档案
+    fn into_uigf(accounts: Vec<Self>) -> UigfV4;
+
+    /// 合并同一账号的另一份记录，按 ID 去重并按 ID 升序排列
+    ///
+    /// 导入时生成 ID 的记录（见 [`is_synthetic_id`]）不按 ID 去重，而是按时间与卡池类型对应真实记录，
+    /// 有对应的真实记录时丢弃；含有此类记录时按时间与 ID 升序排列
+    fn merge(&mut self, other: Self) -> Result<(), Box<dyn Error>> {
+        if self.uid().to_string() != other.uid().to_string() {
+            return Err("UID mismatch".into());
+        }
+        let ids = self
+            .list()
+            .iter()
+            .map(|item| item.id().to_string())
+            .collect::<HashSet<_>>();
+        let list = self.list_mut();
+        list.extend(
+            other
+                .into_list()
+                .into_iter()
+                .filter(|item| !ids.contains(item.id())),
+        );
+
+        let mut real = HashMap::<_, usize>::new();
+        for item in list.iter().filter(|item| !is_synthetic_id(item.id())) {
+            *real
+                .entry((item.time().to_string(), item.gacha_type()))
+                .or_default() += 1;
+        }
+        list.retain(|item| {
+            if !is_synthetic_id(item.id()) {
+                return true;
+            }
+            match real.get_mut(&(item.time().to_string(), item.gacha_type())) {
+                Some(count) if *count > 0 => {
+                    *count -= 1;
+                    false
+                }
+                _ => true,
+            }
+        });
+
+        if list.iter().any(|item| is_synthetic_id(item.id())) {
+            list.sort_by(|a, b| a.time().cmp(b.time()).then_with(|| cmp_id(a.id(), b.id())));
+        } else {
+            list.sort_by(|a, b| cmp_id(a.id(), b.id()));
+        }
+        Ok(())
+    }
+
+    /// 按条件筛选记录，返回只包含匹配记录的同一账号
+    fn filter(&self, filter: &Filter) -> Self {
+        Self::new(
+            self.header(),
+            self.list()
+                .iter()
+                .filter(|item| filter.matches(*item))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// 账号所在的服务器，优先使用获取记录时接口返回的服务器
+    ///
+    /// UIGF 不保存服务器名称，导入的账号根据 UID 判断，与账号的时区不符时返回 `None`，见 [`Region::from_uid`]
+    fn region(&self) -> Option<Region>;
+
+    fn header(&self) -> AccountHeader;
+}
+
+impl GachaItem for Hk4eItem {
+    type GachaType = hk4e::GachaType;
+    type PityGroup = hk4e::UigfGachaType;
+
+    const GAME: Game = Game::Hk4e;
+
+    fn gacha_types() -> &'static [Self::GachaType] {
+        hk4e::GachaType::all_variants()
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn item_id(&self) -> &str {
+        &self.item_id
+    }
+
+    fn time(&self) -> &str {
+        &self.time
+    }
+
+    fn rank_type(&self) -> Option<&str> {
+        self.rank_type.as_deref()
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn gacha_id(&self) -> Option<&str> {
+        None
+    }
+
+    fn gacha_type(&self) -> Self::GachaType {
+        self.gacha_type
+    }
+
+    fn pity_group(&self) -> Self::PityGroup {
+        self.uigf_gacha_type
+    }
+}
+
+impl GachaItem for HkrpgItem {
+    type GachaType = hkrpg::GachaType;
+    type PityGroup = hkrpg::GachaType;
+
+    const GAME: Game = Game::Hkrpg;
+
+    fn gacha_types() -> &'static [Self::GachaType] {
+        hkrpg::GachaType::all_variants()
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn item_id(&self) -> &str {
+        &self.item_id
+    }
+
+    fn time(&self) -> &str {
+        &self.time
+    }
+
+    fn rank_type(&self) -> Option<&str> {
+        self.rank_type.as_deref()
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn gacha_id(&self) -> Option<&str> {
+        Some(&self.gacha_id)
+    }
+
+    fn gacha_type(&self) -> Self::GachaType {
+        self.gacha_type
+    }
+
+    fn pity_group(&self) -> Self::PityGroup {
+        self.gacha_type
+    }
+}
+
+impl GachaItem for NapItem {
+    type GachaType = nap::GachaType;
+    type PityGroup = nap::GachaType;
+
+    const GAME: Game = Game::Nap;
+
+    fn gacha_types() -> &'static [Self::GachaType] {
+        nap::GachaType::all_variants()
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn item_id(&self) -> &str {
+        &self.item_id
+    }
+
+    fn time(&self) -> &str {
+        &self.time
+    }
+
+    fn rank_type(&self) -> Option<&str> {
+        self.rank_type.as_deref()
+    }
+
+    fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    fn gacha_id(&self) -> Option<&str> {
+        self.gacha_id.as_deref()
+    }
+
+    fn gacha_type(&self) -> Self::GachaType {
+        self.gacha_type
+    }
+
+    fn pity_group(&self) -> Self::PityGroup {
+        self.gacha_type
+    }
+}
+
+macro_rules! impl_gacha_account {
+    ($account:ident, $item:ident, $field:ident, $new:ident) => {
+        impl GachaAccount for $account {
+            type Item = $item;
+
+            fn new(header: AccountHeader, list: Vec<Self::Item>) -> Self {
+                Self {
+                    uid: header.uid,
+                    timezone: header.timezone,
+                    lang: header.lang,
+                    list,
+                    region: header.region,
+                }
+            }
+
+            fn uid(&self) -> &Uid {
+                &self.uid
+            }
+
+            fn timezone(&self) -> i64 {
+                self.timezone
+            }
+
+            fn lang(&self) -> Option<LanguageCode> {
+                self.lang
+            }
+
+            fn list(&self) -> &[Self::Item] {
+                &self.list
+            }
+
+            fn list_mut(&mut self) -> &mut Vec<Self::Item> {
+                &mut self.list
+            }
+
+            fn into_list(self) -> Vec<Self::Item> {
+                self.list
+            }
+
+            fn region(&self) -> Option<Region> {
+                self.region.or_else(|| {
+                    Region::from_uid(Self::Item::GAME, &self.uid.to_string())
+                        .filter(|region| region.timezone == self.timezone)
+                })
+            }
+
+            fn header(&self) -> AccountHeader {
+                AccountHeader {
+                    uid: self.uid.clone(),
+                    timezone: self.timezone,
+                    lang: self.lang,
+                    region: self.region,
+                }
+            }
+
+            fn accounts(uigf: &UigfV4) -> Option<&Vec<Self>> {
+                uigf.$field.as_ref()
+            }
+
+            fn accounts_mut(uigf: &mut UigfV4) -> &mut Option<Vec<Self>> {
+                &mut uigf.$field
+            }
+
+            fn into_uigf(accounts: Vec<Self>) -> UigfV4 {
+                UigfV4::$new(accounts)
+            }
+        }
+
+        impl From<$item> for Item {
+            fn from(value: $item) -> Self {
+                Item::$account(value)
+            }
+        }
+    };
+}
+
+impl_gacha_account!(Hk4e, Hk4eItem, hk4e, new_hk4e);
+impl_gacha_account!(Hkrpg, HkrpgItem, hkrpg, new_hkrpg);
+impl_gacha_account!(Nap, NapItem, nap, new_nap);
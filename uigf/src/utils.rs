@@ -1,6 +1,12 @@
-use crate::{hk4e::Hk4e, hkrpg::Hkrpg, nap::Nap, Info, UigfV4};
+use crate::{
+    hk4e::{Hk4e, Hk4eItem},
+    hkrpg::{Hkrpg, HkrpgItem},
+    nap::{Nap, NapItem},
+    Info, Region, UigfV4, UigfVersion,
+};
 use serde::{Deserialize, Serialize};
 use std::{
+    cmp::Ordering,
     error::Error,
     fmt::{Display, Formatter},
 };
@@ -26,8 +32,44 @@ enum_with_str! {
     }
 }
 
-/// 导出档案的时间戳，秒级
+enum_with_str! {
+    /// 游戏
+    Game {
+        /// 原神
+        Hk4e => "hk4e",
+        /// 崩坏：星穹铁道
+        Hkrpg => "hkrpg",
+        /// 绝区零
+        Nap => "nap",
+    }
+}
+
+/// 账号信息，不含抽卡记录
+#[serde_with::skip_serializing_none]
 #[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+pub struct AccountHeader {
+    /// UID
+    pub uid: Uid,
+    /// 时区偏移
+    pub timezone: i64,
+    /// 语言代码
+    pub lang: Option<LanguageCode>,
+    /// 获取记录时接口返回的服务器，见 [`GachaAccount::region`](crate::GachaAccount::region)
+    #[serde(skip)]
+    pub region: Option<Region>,
+}
+
+/// 任一游戏的抽卡记录
+#[derive(Debug, Clone, PartialEq, Hash)]
+pub enum Item {
+    Hk4e(Hk4eItem),
+    Hkrpg(HkrpgItem),
+    Nap(NapItem),
+}
+
+/// 导出档案的时间戳，秒级
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum ExportTimestamp {
     String(String),
@@ -36,6 +78,7 @@ pub enum ExportTimestamp {
 
 /// UID
 #[derive(Debug, Clone, PartialEq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum Uid {
     String(String),
@@ -47,8 +90,11 @@ impl UigfV4 {
         Ok(serde_json::to_string(self)?)
     }
 
+    /// 主版本号不受支持的档案会返回错误
     pub fn from_json(json: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        Ok(serde_json::from_str(json)?)
+        let uigf: Self = serde_json::from_str(json)?;
+        uigf.info.uigf_version()?.ensure_supported()?;
+        Ok(uigf)
     }
 
     pub fn new() -> Self {
@@ -88,17 +134,37 @@ impl UigfV4 {
     }
 }
 
+impl Default for UigfV4 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "schema")]
+impl UigfV4 {
+    /// 由类型定义生成 JSON Schema（draft 2020-12）
+    pub fn json_schema() -> serde_json::Value {
+        schemars::schema_for!(UigfV4).to_value()
+    }
+}
+
 impl Info {
     pub fn new() -> Self {
         Self {
             export_timestamp: ExportTimestamp::now(),
             export_app: concat!("lib", env!("CARGO_CRATE_NAME")).into(),
             export_app_version: env!("CARGO_PKG_VERSION").into(),
-            version: "v4.0".into(),
+            version: UigfVersion::LATEST.to_string(),
         }
     }
 }
 
+impl Default for Info {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ExportTimestamp {
     pub fn now() -> Self {
         ExportTimestamp::Integer(
@@ -149,3 +215,16 @@ impl std::str::FromStr for Uid {
         Ok(Uid::String(s.to_string()))
     }
 }
+
+/// 按数值比较记录 ID，ID 为纯数字字符串，长度不同时位数多者更大
+pub fn cmp_id(a: &str, b: &str) -> Ordering {
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+/// 是否为导入时生成的记录 ID
+///
+/// 不含真实 ID 的档案（如 paimon.moe）导入时以 `0` 开头生成 ID，接口返回的 ID 不会以 `0` 开头；
+/// 这类 ID 与真实 ID 没有先后关系，合并与增量获取时不按 ID 比较
+pub fn is_synthetic_id(id: &str) -> bool {
+    id.starts_with('0')
+}
@@ -0,0 +1,228 @@
+use crate::{Info, UigfV4};
+use serde_json::Value;
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// UIGF 版本号，格式为 `v{major}.{minor}`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UigfVersion {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl UigfVersion {
+    pub const V4_0: Self = Self::new(4, 0);
+    /// 本库支持的最新版本
+    pub const LATEST: Self = Self::V4_0;
+
+    pub const fn new(major: u32, minor: u32) -> Self {
+        Self { major, minor }
+    }
+
+    /// 主版本号不同的档案结构不兼容，无法读取；
+    /// 次版本号新于 [`LATEST`](Self::LATEST) 的档案可能含有本库未知的字段，读取时会丢失，同样拒绝
+    pub fn ensure_supported(self) -> Result<Self, Box<dyn Error>> {
+        if self.major != Self::LATEST.major {
+            return Err(format!(
+                "Unsupported UIGF version {self}, only v{}.x is supported",
+                Self::LATEST.major
+            )
+            .into());
+        }
+        if self > Self::LATEST {
+            return Err(format!(
+                "Unsupported UIGF version {self}, the latest supported version is {}",
+                Self::LATEST
+            )
+            .into());
+        }
+        Ok(self)
+    }
+}
+
+impl Default for UigfVersion {
+    fn default() -> Self {
+        Self::LATEST
+    }
+}
+
+impl Display for UigfVersion {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "v{}.{}", self.major, self.minor)
+    }
+}
+
+impl FromStr for UigfVersion {
+    type Err = Box<dyn Error>;
+
+    /// 接受 `v4.0`、`V4.0` 与 `4.0`，省略次版本号时视为 0
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid UIGF version: {s:?}");
+        let version = s.trim();
+        let version = version.strip_prefix(['v', 'V']).unwrap_or(version);
+        let (major, minor) = version.split_once('.').unwrap_or((version, "0"));
+        Ok(Self {
+            major: major.parse().map_err(|_| invalid())?,
+            minor: minor.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+impl Info {
+    /// 解析档案声明的 UIGF 版本号
+    pub fn uigf_version(&self) -> Result<UigfVersion, Box<dyn Error>> {
+        self.version.parse()
+    }
+}
+
+/// 某一次版本中各层级已知的字段
+struct Fields {
+    root: &'static [&'static str],
+    info: &'static [&'static str],
+    account: &'static [&'static str],
+    hk4e: &'static [&'static str],
+    hkrpg: &'static [&'static str],
+    nap: &'static [&'static str],
+}
+
+/// 各次版本已知的字段，降级时去除目标版本未知的字段，新增次版本时在此添加一项
+const VERSIONS: &[(UigfVersion, Fields)] = &[(
+    UigfVersion::V4_0,
+    Fields {
+        root: &["info", "hk4e", "hkrpg", "nap"],
+        info: &[
+            "export_timestamp",
+            "export_app",
+            "export_app_version",
+            "version",
+        ],
+        account: &["uid", "timezone", "lang", "list"],
+        hk4e: &[
+            "uigf_gacha_type",
+            "gacha_type",
+            "item_id",
+            "count",
+            "time",
+            "name",
+            "item_type",
+            "rank_type",
+            "id",
+        ],
+        hkrpg: &[
+            "gacha_id",
+            "gacha_type",
+            "item_id",
+            "count",
+            "time",
+            "name",
+            "item_type",
+            "rank_type",
+            "id",
+        ],
+        nap: &[
+            "gacha_id",
+            "gacha_type",
+            "item_id",
+            "count",
+            "time",
+            "name",
+            "item_type",
+            "rank_type",
+            "id",
+        ],
+    },
+)];
+
+impl UigfV4 {
+    /// 转换为指定的 UIGF 次版本，去除该版本不支持的字段，以便交给旧版工具读取
+    ///
+    /// 目标版本不在字段表中时返回错误
+    pub fn downgrade(&self, target: UigfVersion) -> Result<Self, Box<dyn Error>> {
+        target.ensure_supported()?;
+        let fields = VERSIONS
+            .iter()
+            .find(|(version, _)| *version == target)
+            .map(|(_, fields)| fields)
+            .ok_or_else(|| format!("Unknown UIGF version {target}"))?;
+
+        let mut value = serde_json::to_value(self)?;
+        fields.retain(&mut value);
+        let mut uigf = serde_json::from_value::<Self>(value)?;
+        uigf.info.version = target.to_string();
+        Ok(uigf)
+    }
+}
+
+impl Fields {
+    /// 去除档案中该版本未知的字段
+    fn retain(&self, value: &mut Value) {
+        retain_fields(value, self.root);
+        if let Some(info) = value.get_mut("info") {
+            retain_fields(info, self.info);
+        }
+        for (game, item_fields) in [
+            ("hk4e", self.hk4e),
+            ("hkrpg", self.hkrpg),
+            ("nap", self.nap),
+        ] {
+            let accounts = value.get_mut(game).and_then(Value::as_array_mut);
+            for account in accounts.into_iter().flatten() {
+                retain_fields(account, self.account);
+                let list = account.get_mut("list").and_then(Value::as_array_mut);
+                for item in list.into_iter().flatten() {
+                    retain_fields(item, item_fields);
+                }
+            }
+        }
+    }
+}
+
+fn retain_fields(value: &mut Value, fields: &[&str]) {
+    if let Some(object) = value.as_object_mut() {
+        object.retain(|key, _| fields.contains(&key.as_str()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 目前只有 v4.0，以去除 `lang` 与 `count` 的字段表模拟较旧的次版本
+    #[test]
+    fn retain_removes_fields_unknown_to_target() {
+        let v4_0 = &VERSIONS[0].1;
+        let older = Fields {
+            account: &["uid", "timezone", "list"],
+            hk4e: &[
+                "uigf_gacha_type",
+                "gacha_type",
+                "item_id",
+                "time",
+                "name",
+                "item_type",
+                "rank_type",
+                "id",
+            ],
+            ..*v4_0
+        };
+        let mut value = serde_json::json!({
+            "info": {"export_timestamp": 1704081600, "export_app": "test", "export_app_version": "1.0", "version": "v4.0"},
+            "hk4e": [{"uid": "100000001", "timezone": 8, "lang": "zh-cn", "list": [
+                {"uigf_gacha_type": "301", "gacha_type": "301", "item_id": "10000046", "count": "1", "time": "2024-01-01 12:00:00", "id": "1"}
+            ]}],
+            "unknown": true
+        });
+        older.retain(&mut value);
+        assert_eq!(value.get("unknown"), None);
+        let account = &value["hk4e"][0];
+        assert_eq!(account.get("lang"), None);
+        assert_eq!(account["uid"], "100000001");
+        let item = &account["list"][0];
+        assert_eq!(item.get("count"), None);
+        assert_eq!(item["time"], "2024-01-01 12:00:00");
+        assert_eq!(item["item_id"], "10000046");
+    }
+}
@@ -0,0 +1,73 @@
+use uigf::{
+    anonymize::{Anonymizer, TimePrecision},
+    hk4e::Hk4e,
+    UigfV4,
+};
+
+fn account(uid: &str) -> Hk4e {
+    serde_json::from_str(&format!(
+        r#"{{"uid": "{uid}", "timezone": 8, "list": [
+            {{"uigf_gacha_type": "301", "gacha_type": "301", "item_id": "10000046", "time": "2024-01-01 12:34:56", "id": "1704081600000000001"}}
+        ]}}"#
+    ))
+    .unwrap()
+}
+
+#[test]
+fn pseudonyms_avoid_original_uids() {
+    let anonymizer = Anonymizer::new("salt");
+    let (_, mapping) = anonymizer
+        .anonymize(&UigfV4::new_hk4e(vec![account("100000001")]))
+        .unwrap();
+    let taken = mapping.uids[0].pseudonym.to_string();
+    assert!(taken.starts_with('1'));
+
+    // 另一账号的原 UID 恰好是第一个账号的假名
+    let uigf = UigfV4::new_hk4e(vec![account("100000001"), account(&taken)]);
+    let (anonymized, mapping) = anonymizer.anonymize(&uigf).unwrap();
+    let pseudonyms = anonymized
+        .hk4e
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(|account| account.uid.to_string())
+        .collect::<Vec<_>>();
+    assert!(!pseudonyms.contains(&"100000001".to_string()));
+    assert!(!pseudonyms.contains(&taken));
+    assert_ne!(pseudonyms[0], pseudonyms[1]);
+
+    assert_eq!(mapping.restore(&anonymized).unwrap(), uigf);
+}
+
+#[test]
+fn shifted_ids_and_rounded_times() {
+    let uigf = UigfV4::new_hk4e(vec![account("100000001")]);
+    let (anonymized, mapping) = Anonymizer::new("salt")
+        .shift_ids(-1000)
+        .round_times(TimePrecision::Hour)
+        .anonymize(&uigf)
+        .unwrap();
+    let item = &anonymized.hk4e.as_ref().unwrap()[0].list[0];
+    assert_eq!(item.id, "1704081599999999001");
+    assert_eq!(item.time, "2024-01-01 12:00:00");
+
+    let restored = mapping.restore(&anonymized).unwrap();
+    let item = &restored.hk4e.as_ref().unwrap()[0].list[0];
+    assert_eq!(item.id, "1704081600000000001");
+    assert_eq!(restored.hk4e.unwrap()[0].uid.to_string(), "100000001");
+}
+
+#[test]
+fn synthetic_ids_keep_leading_zero() {
+    let mut uigf = UigfV4::new_hk4e(vec![account("100000001")]);
+    uigf.hk4e.as_mut().unwrap()[0].list[0].id = "0202401011200000000".into();
+    let (anonymized, mapping) = Anonymizer::new("salt")
+        .shift_ids(-1000)
+        .anonymize(&uigf)
+        .unwrap();
+    let id = &anonymized.hk4e.as_ref().unwrap()[0].list[0].id;
+    assert_eq!(id, "0202401011199999000");
+    assert!(uigf::is_synthetic_id(id));
+
+    assert_eq!(mapping.restore(&anonymized).unwrap(), uigf);
+}
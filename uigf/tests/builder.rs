@@ -0,0 +1,58 @@
+use uigf::{hk4e::Hk4e, ExportTimestamp, Info, UigfV4};
+
+#[test]
+fn unset_fields_match_info_new() {
+    let info = Info::builder().build();
+    let default = Info::new();
+    assert_eq!(info.export_app, default.export_app);
+    assert_eq!(info.export_app_version, default.export_app_version);
+    assert_eq!(info.version, default.version);
+}
+
+#[test]
+fn info_builder_sets_every_field() {
+    let info = Info::builder()
+        .export_app("app")
+        .export_app_version("2.0")
+        .export_timestamp(ExportTimestamp::Integer(1704081600))
+        .version("v4.0")
+        .build();
+    assert_eq!(
+        info,
+        Info {
+            export_timestamp: ExportTimestamp::Integer(1704081600),
+            export_app: "app".into(),
+            export_app_version: "2.0".into(),
+            version: "v4.0".into(),
+        }
+    );
+}
+
+#[test]
+fn uigf_builder_sets_info_and_accounts() {
+    let account: Hk4e =
+        serde_json::from_str(r#"{"uid": "100000001", "timezone": 8, "list": []}"#).unwrap();
+    let uigf = UigfV4::builder()
+        .export_app("app")
+        .export_app_version("2.0")
+        .export_timestamp(ExportTimestamp::Integer(1704081600))
+        .hk4e(vec![account.clone()])
+        .build();
+    assert_eq!(uigf.info.export_app, "app");
+    assert_eq!(uigf.info.export_app_version, "2.0");
+    assert_eq!(
+        uigf.info.export_timestamp,
+        ExportTimestamp::Integer(1704081600)
+    );
+    assert_eq!(uigf.info.version, Info::new().version);
+    assert_eq!(uigf.hk4e, Some(vec![account]));
+    assert!(uigf.hkrpg.is_none());
+    assert!(uigf.nap.is_none());
+}
+
+#[test]
+fn with_info_replaces_info() {
+    let info = Info::builder().export_app("app").build();
+    let uigf = UigfV4::new().with_info(info.clone());
+    assert_eq!(uigf.info, info);
+}
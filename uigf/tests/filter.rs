@@ -0,0 +1,120 @@
+use uigf::{Filter, GachaAccount, GachaItem, UigfV4};
+
+const UIGF: &str = r#"{
+    "info": {"export_timestamp": 1704081600, "export_app": "test", "export_app_version": "1.0", "version": "v4.0"},
+    "hk4e": [
+        {"uid": "100000001", "timezone": 8, "list": [
+            {"uigf_gacha_type": "200", "gacha_type": "200", "item_id": "15304", "time": "2024-01-01 12:00:00", "name": "弹弓", "rank_type": "3", "id": "9"},
+            {"uigf_gacha_type": "301", "gacha_type": "301", "item_id": "10000046", "time": "2024-01-02 12:00:00", "name": "胡桃", "rank_type": "5", "id": "10"},
+            {"uigf_gacha_type": "301", "gacha_type": "400", "item_id": "10000046", "time": "2024-01-03 12:00:00", "name": "胡桃", "rank_type": "5", "id": "1000"}
+        ]},
+        {"uid": "100000002", "timezone": 8, "list": [
+            {"uigf_gacha_type": "200", "gacha_type": "200", "item_id": "15304", "time": "2024-01-01 12:00:00", "name": "弹弓", "rank_type": "3", "id": "20"}
+        ]}
+    ],
+    "hkrpg": [{"uid": "600000001", "timezone": -5, "list": [
+        {"gacha_id": "2001", "gacha_type": "11", "item_id": "1208", "time": "2024-01-01 12:00:00", "name": "Fu Xuan", "rank_type": "5", "id": "30"},
+        {"gacha_id": "1001", "gacha_type": "1", "item_id": "20000", "time": "2024-01-02 12:00:00", "name": "Arrows", "rank_type": "3", "id": "31"}
+    ]}]
+}"#;
+
+fn ids(filter: &Filter) -> Vec<String> {
+    let uigf = UigfV4::from_json(UIGF).unwrap();
+    let mut ids = Vec::new();
+    for account in uigf.hk4e.iter().flatten() {
+        ids.extend(
+            account
+                .list()
+                .iter()
+                .filter(|item| filter.matches(*item))
+                .map(|item| item.id().to_string()),
+        );
+    }
+    for account in uigf.hkrpg.iter().flatten() {
+        ids.extend(
+            account
+                .list()
+                .iter()
+                .filter(|item| filter.matches(*item))
+                .map(|item| item.id().to_string()),
+        );
+    }
+    ids
+}
+
+#[test]
+fn empty_filter_matches_everything() {
+    assert_eq!(ids(&Filter::new()), ["9", "10", "1000", "20", "30", "31"]);
+}
+
+#[test]
+fn time_range() {
+    assert_eq!(
+        ids(&Filter::new().time_range("2024-01-02 00:00:00"..)),
+        ["10", "1000", "31"]
+    );
+    assert_eq!(
+        ids(&Filter::new().time_range("2024-01-01 12:00:00".."2024-01-02 12:00:00")),
+        ["9", "20", "30"]
+    );
+    assert_eq!(
+        ids(&Filter::new().time_range("2024-01-01 12:00:00"..="2024-01-02 12:00:00")),
+        ["9", "10", "20", "30", "31"]
+    );
+}
+
+#[test]
+fn id_range_compares_numerically() {
+    // 按字典序 "10" 与 "1000" 都小于 "9"
+    assert_eq!(ids(&Filter::new().id_range("9".."30")), ["9", "10", "20"]);
+    assert_eq!(ids(&Filter::new().id_range("100"..)), ["1000"]);
+    assert_eq!(ids(&Filter::new().id_range(..="10")), ["9", "10"]);
+}
+
+#[test]
+fn pool_matches_gacha_type_or_pity_group() {
+    // 角色活动祈愿-2 (400) 与 301 共享保底
+    assert_eq!(ids(&Filter::new().pool("301")), ["10", "1000"]);
+    assert_eq!(ids(&Filter::new().pool("400")), ["1000"]);
+    assert_eq!(
+        ids(&Filter::new().pool("200").pool("11")),
+        ["9", "20", "30"]
+    );
+}
+
+#[test]
+fn rank_name_and_item_id() {
+    assert_eq!(ids(&Filter::new().rank("5")), ["10", "1000", "30"]);
+    assert_eq!(
+        ids(&Filter::new().name("弹弓").name("Arrows")),
+        ["9", "20", "31"]
+    );
+    assert_eq!(ids(&Filter::new().item_id("10000046")), ["10", "1000"]);
+}
+
+#[test]
+fn gacha_id_never_matches_hk4e() {
+    assert_eq!(ids(&Filter::new().gacha_id("2001")), ["30"]);
+}
+
+#[test]
+fn conditions_are_combined() {
+    assert_eq!(
+        ids(&Filter::new().rank("5").time_range("2024-01-02 00:00:00"..)),
+        ["10", "1000"]
+    );
+}
+
+#[test]
+fn document_filter_keeps_empty_accounts() {
+    let uigf = UigfV4::from_json(UIGF).unwrap();
+    let filtered = uigf.filter(&Filter::new().pool("301"));
+
+    assert_eq!(filtered.info, uigf.info);
+    let hk4e = filtered.hk4e.unwrap();
+    assert_eq!(hk4e.len(), 2);
+    assert_eq!(hk4e[0].list.len(), 2);
+    assert!(hk4e[1].list.is_empty());
+    assert!(filtered.hkrpg.unwrap()[0].list.is_empty());
+    assert!(filtered.nap.is_none());
+}
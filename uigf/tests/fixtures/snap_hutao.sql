@@ -0,0 +1,22 @@
+-- Snap Hutao 的 Userdata.db 中与抽卡记录相关的两张表，只保留导入时读取的列
+CREATE TABLE gacha_archives (InnerId TEXT PRIMARY KEY, Uid TEXT NOT NULL);
+CREATE TABLE gacha_items (
+    InnerId TEXT PRIMARY KEY,
+    ArchiveId TEXT NOT NULL,
+    GachaType INTEGER NOT NULL,
+    QueryType INTEGER NOT NULL,
+    ItemId INTEGER NOT NULL,
+    Time TEXT NOT NULL,
+    Id NOT NULL
+);
+
+INSERT INTO gacha_archives VALUES ('a1', '100000001'), ('a2', '100000002'), ('a3', '100000003');
+
+-- ID 混用 TEXT 与 INTEGER，按字典序排列时 '1000' 会排在 '999' 之前
+INSERT INTO gacha_items VALUES
+    ('i1', 'a1', 301, 301, 10000046, '2024-01-01T12:00:01.0000000+08:00', '1000'),
+    ('i2', 'a1', 400, 301, 15304, '2024-01-01T12:00:00.0000000+08:00', '999'),
+    ('i3', 'a1', 200, 200, 15304, '2024-01-01T12:00:02.0000000+08:00', 1001),
+    ('i4', 'a2', 200, 200, 15304, '2024-01-01T12:00:00.0000000+08:00', 2000),
+    -- 不是整小时的时区偏移
+    ('i5', 'a3', 200, 200, 15304, '2024-01-01T12:00:00.0000000+05:30', 3000);
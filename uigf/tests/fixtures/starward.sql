@@ -0,0 +1,18 @@
+-- Starward 数据库中三个游戏的抽卡记录表，只保留导入时读取的列
+CREATE TABLE GenshinGachaItem (Uid INTEGER NOT NULL, Id TEXT NOT NULL, GachaId INTEGER, GachaType INTEGER NOT NULL, ItemId INTEGER NOT NULL, Count INTEGER, Time TEXT NOT NULL, Name TEXT, ItemType TEXT, RankType INTEGER, Lang TEXT);
+CREATE TABLE StarRailGachaItem (Uid INTEGER NOT NULL, Id TEXT NOT NULL, GachaId INTEGER, GachaType INTEGER NOT NULL, ItemId INTEGER NOT NULL, Count INTEGER, Time TEXT NOT NULL, Name TEXT, ItemType TEXT, RankType INTEGER, Lang TEXT);
+CREATE TABLE ZZZGachaItem (Uid INTEGER NOT NULL, Id TEXT NOT NULL, GachaId INTEGER, GachaType INTEGER NOT NULL, ItemId INTEGER NOT NULL, Count INTEGER, Time TEXT NOT NULL, Name TEXT, ItemType TEXT, RankType INTEGER, Lang TEXT);
+
+-- ID 以 TEXT 存储，按字典序排列时 '1000' 会排在 '999' 之前
+INSERT INTO GenshinGachaItem VALUES
+    (100000001, '1000', NULL, 301, 10000046, 1, '2024-01-01 12:00:01', '胡桃', '角色', 5, 'zh-CN'),
+    (100000001, '999', NULL, 400, 15304, 1, '2024-01-01 12:00:00', '弹弓', '武器', 3, 'zh-CN'),
+    (100000002, '2000', NULL, 200, 15304, 1, '2024-01-01 12:00:00', '弹弓', '武器', 3, 'zh-CN');
+
+INSERT INTO StarRailGachaItem VALUES
+    (600000001, '1000', 2001, 11, 1208, 1, '2024-01-01 12:00:01', 'Fu Xuan', 'Character', 5, 'en-US'),
+    (600000001, '999', 1001, 1, 20000, 1, '2024-01-01 12:00:00', 'Arrows', 'Light Cone', 3, 'en-US');
+
+INSERT INTO ZZZGachaItem VALUES
+    (10000001, '1000', 2001, 2, 1191, 1, '2024-07-04 12:00:00', '艾莲', '代理人', 4, 'zh-CN'),
+    (10000001, '999', 5001, 5, 54001, 1, '2024-07-04 12:00:01', '鲨牙布', '邦布', 3, 'zh-CN');
@@ -0,0 +1,134 @@
+use uigf::{
+    hk4e::Hk4eItem,
+    is_synthetic_id,
+    paimon_moe::{ItemMapping, ItemTable, PaimonMoe},
+    GachaAccount,
+};
+
+const PAIMON_MOE: &str = r#"{
+    "wish-uid": "100000001",
+    "wish-counter-character-event": {
+        "total": 3,
+        "legendary": 0,
+        "rare": 2,
+        "pulls": [
+            {"type": "weapon", "code": "301", "id": "slingshot", "time": "2024-01-01 12:00:00", "pity": 1},
+            {"type": "weapon", "code": "301", "id": "slingshot", "time": "2024-01-01 12:00:00", "pity": 1},
+            {"type": "character", "code": "301", "id": "hu_tao", "time": "2024-01-01 12:00:01", "pity": 3}
+        ]
+    },
+    "wish-counter-standard": {
+        "total": 1,
+        "legendary": 1,
+        "rare": 1,
+        "pulls": [
+            {"type": "weapon", "code": "200", "id": "slingshot", "time": "2023-12-31 08:00:00", "pity": 1}
+        ]
+    }
+}"#;
+
+fn table() -> ItemTable {
+    let mapping =
+        |key: &str, item_id: &str, name: &str, item_type: &str, rank_type: &str| ItemMapping {
+            key: key.into(),
+            item_id: item_id.into(),
+            name: name.into(),
+            item_type: item_type.into(),
+            rank_type: rank_type.into(),
+        };
+    ItemTable::new(vec![
+        mapping("hu_tao", "10000046", "胡桃", "角色", "5"),
+        mapping("slingshot", "15304", "弹弓", "武器", "3"),
+    ])
+}
+
+#[test]
+fn to_hk4e_generates_ordered_synthetic_ids() {
+    let paimon = PaimonMoe::from_json(PAIMON_MOE).unwrap();
+    let hk4e = paimon.to_hk4e(&table(), 8, None).unwrap();
+
+    let ids = hk4e
+        .list
+        .iter()
+        .map(|item| item.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ids,
+        [
+            "0202312310800000000",
+            "0202401011200000000",
+            "0202401011200000001",
+            "0202401011200010000",
+        ]
+    );
+    assert!(ids.iter().all(|id| is_synthetic_id(id)));
+    assert_eq!(hk4e.list[3].name.as_deref(), Some("胡桃"));
+    assert_eq!(hk4e.list[0].gacha_type.to_string(), "200");
+}
+
+#[test]
+fn to_hk4e_rejects_invalid_time() {
+    let json = PAIMON_MOE.replace("2023-12-31 08:00:00", "2023-12-31");
+    let paimon = PaimonMoe::from_json(&json).unwrap();
+    assert!(paimon.to_hk4e(&table(), 8, None).is_err());
+}
+
+#[test]
+fn round_trip_through_hk4e() {
+    let paimon = PaimonMoe::from_json(PAIMON_MOE).unwrap();
+    let hk4e = paimon.to_hk4e(&table(), 8, None).unwrap();
+    let back = PaimonMoe::from_hk4e(&hk4e, &table()).unwrap();
+
+    assert_eq!(back.uid.as_deref(), Some("100000001"));
+    let character = back.character_event.unwrap();
+    assert_eq!(character.total, 3);
+    assert_eq!(
+        character
+            .pulls
+            .iter()
+            .map(|pull| (pull.id.as_str(), pull.pity))
+            .collect::<Vec<_>>(),
+        [("slingshot", 1), ("slingshot", 1), ("hu_tao", 3)]
+    );
+    assert_eq!(back.standard.unwrap().total, 1);
+}
+
+#[test]
+fn merge_drops_synthetic_duplicates_of_real_records() {
+    let paimon = PaimonMoe::from_json(PAIMON_MOE).unwrap();
+    let mut hk4e = paimon.to_hk4e(&table(), 8, None).unwrap();
+
+    let mut real = hk4e.clone();
+    real.list = vec![
+        Hk4eItem {
+            id: "1704081600000000001".into(),
+            ..hk4e.list[1].clone()
+        },
+        Hk4eItem {
+            id: "1704081601000000002".into(),
+            ..hk4e.list[3].clone()
+        },
+        Hk4eItem {
+            id: "1704168000000000003".into(),
+            time: "2024-01-02 12:00:00".into(),
+            ..hk4e.list[3].clone()
+        },
+    ];
+    hk4e.merge(real).unwrap();
+
+    let ids = hk4e
+        .list
+        .iter()
+        .map(|item| item.id.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ids,
+        [
+            "0202312310800000000",
+            "0202401011200000001",
+            "1704081600000000001",
+            "1704081601000000002",
+            "1704168000000000003",
+        ]
+    );
+}
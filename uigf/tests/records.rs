@@ -0,0 +1,73 @@
+use uigf::{Game, UigfV4};
+
+const UIGF: &str = r#"{
+    "info": {"export_timestamp": 1704081600, "export_app": "test", "export_app_version": "1.0", "version": "v4.0"},
+    "hk4e": [{"uid": "100000001", "timezone": 8, "list": [
+        {"uigf_gacha_type": "301", "gacha_type": "400", "item_id": "10000046", "time": "2024-01-01 12:00:00", "name": "胡桃", "rank_type": "5", "id": "1704081600000000001"}
+    ]}],
+    "hkrpg": [{"uid": 600000001, "timezone": -5, "list": [
+        {"gacha_id": "2001", "gacha_type": "11", "item_id": "1208", "time": "2024-01-01 12:00:01", "id": "1704081601000000002"}
+    ]}],
+    "nap": [{"uid": "10000001", "timezone": 8, "list": [
+        {"gacha_type": "2", "item_id": "1191", "time": "2024-07-04 12:00:00", "id": "1720065600000000003"}
+    ]}]
+}"#;
+
+#[test]
+fn record_views_read_common_fields() {
+    let uigf = UigfV4::from_json(UIGF).unwrap();
+    let records = uigf
+        .records()
+        .map(|record| {
+            let item = record.item;
+            (
+                item.game(),
+                item.id().to_string(),
+                item.gacha_type(),
+                item.name().map(str::to_string),
+                record.timezone,
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        records,
+        [
+            (
+                Game::Hk4e,
+                "1704081600000000001".to_string(),
+                "400".to_string(),
+                Some("胡桃".to_string()),
+                8,
+            ),
+            (
+                Game::Hkrpg,
+                "1704081601000000002".to_string(),
+                "11".to_string(),
+                None,
+                -5,
+            ),
+            (
+                Game::Nap,
+                "1720065600000000003".to_string(),
+                "2".to_string(),
+                None,
+                8,
+            ),
+        ]
+    );
+}
+
+#[test]
+fn record_views_agree_with_each_other() {
+    let mut uigf = UigfV4::from_json(UIGF).unwrap();
+    for mut record in uigf.records_mut() {
+        *record.item.time_mut() = "2024-01-02 00:00:00".into();
+    }
+    let owned = uigf.clone().into_records().collect::<Vec<_>>();
+    for (record, owned) in uigf.records().zip(&owned) {
+        assert_eq!(record.item.cloned(), owned.item);
+        assert_eq!(owned.item.time(), "2024-01-02 00:00:00");
+        assert_eq!(record.item.item_id(), owned.item.as_ref().item_id());
+        assert_eq!(record.item.rank_type(), owned.item.rank_type());
+    }
+}
@@ -0,0 +1,103 @@
+use std::collections::HashSet;
+use uigf::{hk4e::Hk4e, nap::Nap, GachaAccount, Game, Region, Uid};
+
+#[test]
+fn from_uid() {
+    let cases = [
+        (Game::Hk4e, "100000001", Some("cn_gf01")),
+        (Game::Hk4e, "500000001", Some("cn_qd01")),
+        (Game::Hk4e, "600000001", Some("os_usa")),
+        (Game::Hk4e, "700000001", Some("os_euro")),
+        (Game::Hk4e, "800000001", Some("os_asia")),
+        (Game::Hk4e, "900000001", Some("os_cht")),
+        (Game::Hk4e, "1800000001", Some("os_asia")),
+        (Game::Hk4e, "000000001", None),
+        (Game::Hkrpg, "100000001", Some("prod_gf_cn")),
+        (Game::Hkrpg, "500000001", Some("prod_qd_cn")),
+        (Game::Hkrpg, "600000001", Some("prod_official_usa")),
+        (Game::Hkrpg, "700000001", Some("prod_official_eur")),
+        (Game::Hkrpg, "800000001", Some("prod_official_asia")),
+        (Game::Hkrpg, "900000001", Some("prod_official_cht")),
+        (Game::Nap, "10000001", Some("prod_gf_cn")),
+        (Game::Nap, "1000000001", Some("prod_gf_us")),
+        (Game::Nap, "1500000001", Some("prod_gf_eu")),
+        (Game::Nap, "1300000001", Some("prod_gf_jp")),
+        (Game::Nap, "1700000001", Some("prod_gf_sg")),
+        (Game::Nap, "1900000001", None),
+        (Game::Hk4e, "", None),
+        (Game::Hk4e, "8abc", None),
+    ];
+    for (game, uid, name) in cases {
+        assert_eq!(
+            Region::from_uid(game, uid).map(|region| region.name),
+            name,
+            "{game:?} {uid}"
+        );
+    }
+}
+
+#[test]
+fn from_name() {
+    let cases = [
+        (Game::Hk4e, "os_usa", Some((-5, true))),
+        (Game::Hk4e, "cn_gf01", Some((8, false))),
+        (Game::Hkrpg, "prod_official_eur", Some((1, true))),
+        (Game::Nap, "prod_gf_cn", Some((8, false))),
+        (Game::Hkrpg, "prod_gf_cn", Some((8, false))),
+        (Game::Hk4e, "prod_gf_cn", None),
+        (Game::Nap, "unknown", None),
+    ];
+    for (game, name, expected) in cases {
+        assert_eq!(
+            Region::from_name(game, name).map(|region| (region.timezone, region.global)),
+            expected,
+            "{game:?} {name}"
+        );
+    }
+}
+
+#[test]
+fn names_are_unique_per_game() {
+    let mut names = HashSet::new();
+    for region in Region::all() {
+        assert!(names.insert((region.game, region.name)), "{}", region.name);
+        assert_eq!(Region::from_name(region.game, region.name), Some(*region));
+    }
+}
+
+#[test]
+fn account_region_checks_timezone() {
+    let account = Hk4e {
+        uid: Uid::String("600000001".into()),
+        timezone: -5,
+        lang: None,
+        list: Vec::new(),
+        region: None,
+    };
+    assert_eq!(account.region().map(|region| region.name), Some("os_usa"));
+
+    let account = Hk4e {
+        timezone: 8,
+        ..account
+    };
+    assert_eq!(account.region(), None);
+
+    let account = Hk4e {
+        region: Region::from_name(Game::Hk4e, "os_usa"),
+        ..account
+    };
+    assert_eq!(account.region().map(|region| region.name), Some("os_usa"));
+    assert_eq!(account.header().region, account.region);
+
+    let account = Nap {
+        uid: Uid::Integer(10000001),
+        timezone: 8,
+        lang: None,
+        list: Vec::new(),
+        region: None,
+    };
+    assert_eq!(
+        account.region().map(|region| region.name),
+        Some("prod_gf_cn")
+    );
+}
@@ -0,0 +1,122 @@
+use uigf::{GachaAccount, GachaItem, SerializeOptions, UigfV4};
+
+/// 账号与记录均未排序
+const UIGF: &str = r#"{
+    "info": {"export_timestamp": 1704081600, "export_app": "test", "export_app_version": "1.0", "version": "v4.0"},
+    "hk4e": [
+        {"uid": "100000002", "timezone": 8, "list": [
+            {"uigf_gacha_type": "200", "gacha_type": "200", "item_id": "15304", "time": "2024-01-01 12:00:00", "id": "20"}
+        ]},
+        {"uid": "100000001", "timezone": 8, "list": [
+            {"uigf_gacha_type": "301", "gacha_type": "301", "item_id": "10000046", "time": "2024-01-03 12:00:00", "id": "1000"},
+            {"uigf_gacha_type": "200", "gacha_type": "200", "item_id": "15304", "time": "2024-01-01 12:00:00", "id": "9"},
+            {"uigf_gacha_type": "301", "gacha_type": "301", "item_id": "10000046", "time": "2024-01-02 12:00:00", "id": "10"}
+        ]},
+        {"uid": "10000000", "timezone": 8, "list": []}
+    ]
+}"#;
+
+fn uigf() -> UigfV4 {
+    UigfV4::from_json(UIGF).unwrap()
+}
+
+#[test]
+fn canonicalize_sorts_accounts_and_records_numerically() {
+    let mut uigf = uigf();
+    uigf.canonicalize();
+    let accounts = uigf.hk4e.unwrap();
+    assert_eq!(
+        accounts
+            .iter()
+            .map(|account| account.uid().to_string())
+            .collect::<Vec<_>>(),
+        ["10000000", "100000001", "100000002"]
+    );
+    assert_eq!(
+        accounts[1]
+            .list()
+            .iter()
+            .map(|item| item.id())
+            .collect::<Vec<_>>(),
+        ["9", "10", "1000"]
+    );
+}
+
+#[test]
+fn canonical_output_is_stable() {
+    let options = SerializeOptions::new().canonical(true).indent(2);
+    let first = uigf().to_json_with(&options).unwrap();
+    assert_eq!(uigf().to_json_with(&options).unwrap(), first);
+
+    // 顺序不同但内容相同的档案得到相同的输出
+    let mut sorted = uigf();
+    sorted.canonicalize();
+    sorted.hk4e.as_mut().unwrap().reverse();
+    assert_eq!(sorted.to_json_with(&options).unwrap(), first);
+
+    let mut writer = Vec::new();
+    uigf().to_writer_with(&mut writer, &options).unwrap();
+    assert_eq!(String::from_utf8(writer).unwrap(), first);
+}
+
+#[test]
+fn compact_by_default() {
+    let json = uigf().to_json_with(&SerializeOptions::new()).unwrap();
+    assert!(!json.contains('\n'));
+    assert_eq!(UigfV4::from_json(&json).unwrap(), uigf());
+}
+
+#[test]
+fn indent_width() {
+    let json = uigf()
+        .to_json_with(&SerializeOptions::new().indent(4))
+        .unwrap();
+    let lines = json.lines().collect::<Vec<_>>();
+    assert_eq!(lines[0], "{");
+    assert_eq!(lines[1], r#"    "info": {"#);
+    assert_eq!(lines[2], r#"        "export_timestamp": 1704081600,"#);
+    assert_eq!(UigfV4::from_json(&json).unwrap(), uigf());
+}
+
+#[test]
+fn one_record_per_line() {
+    let json = uigf()
+        .to_json_with(&SerializeOptions::new().one_record_per_line(true))
+        .unwrap();
+    let records = json
+        .lines()
+        .filter(|line| line.contains(r#""item_id""#))
+        .collect::<Vec<_>>();
+    assert_eq!(records.len(), 4);
+    for record in &records {
+        let record = record.trim().trim_end_matches(',');
+        assert!(record.starts_with('{') && record.ends_with('}'), "{record}");
+    }
+    // 未设置缩进时缩进 2 个空格，记录位于第 4 层
+    assert!(records[0].starts_with(&format!("{}{{", " ".repeat(8))));
+    assert!(json.contains("\n  \"info\": {"));
+    assert_eq!(UigfV4::from_json(&json).unwrap(), uigf());
+}
+
+#[test]
+fn one_record_per_line_with_indent() {
+    let json = uigf()
+        .to_json_with(
+            &SerializeOptions::new()
+                .one_record_per_line(true)
+                .indent(1)
+                .canonical(true),
+        )
+        .unwrap();
+    let ids = json
+        .lines()
+        .filter(|line| line.starts_with("    {"))
+        .map(|line| {
+            let record =
+                serde_json::from_str::<serde_json::Value>(line.trim().trim_end_matches(','))
+                    .unwrap();
+            record["id"].as_str().unwrap().to_string()
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(ids, ["9", "10", "1000", "20"]);
+}
@@ -0,0 +1,77 @@
+#![cfg(feature = "sqlite")]
+
+use rusqlite::Connection;
+use std::{path::PathBuf, sync::OnceLock};
+use uigf::{
+    sqlite::{snap_hutao, starward},
+    GachaItem,
+};
+
+/// 由 `tests/fixtures` 中的 SQL 生成数据库，每个测试进程只生成一次
+fn fixture(name: &str, sql: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("uigf-{}-{name}.db", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    Connection::open(&path).unwrap().execute_batch(sql).unwrap();
+    path
+}
+
+fn snap_hutao() -> &'static PathBuf {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    PATH.get_or_init(|| fixture("snap_hutao", include_str!("fixtures/snap_hutao.sql")))
+}
+
+fn starward() -> &'static PathBuf {
+    static PATH: OnceLock<PathBuf> = OnceLock::new();
+    PATH.get_or_init(|| fixture("starward", include_str!("fixtures/starward.sql")))
+}
+
+fn ids<I: GachaItem>(list: &[I]) -> Vec<&str> {
+    list.iter().map(GachaItem::id).collect()
+}
+
+#[test]
+fn snap_hutao_hk4e() {
+    let hk4e = snap_hutao::import_hk4e(snap_hutao(), "100000001").unwrap();
+    assert_eq!(hk4e.timezone, 8);
+    assert_eq!(ids(&hk4e.list), ["999", "1000", "1001"]);
+    assert_eq!(hk4e.list[0].gacha_type.to_string(), "400");
+    assert_eq!(hk4e.list[0].uigf_gacha_type.to_string(), "301");
+    assert_eq!(hk4e.list[0].time, "2024-01-01 12:00:00");
+
+    let other = snap_hutao::import_hk4e(snap_hutao(), "100000002").unwrap();
+    assert_eq!(ids(&other.list), ["2000"]);
+    assert!(snap_hutao::import_hk4e(snap_hutao(), "100000009").is_err());
+}
+
+#[test]
+fn snap_hutao_rejects_partial_hour_offsets() {
+    assert!(snap_hutao::import_hk4e(snap_hutao(), "100000003").is_err());
+}
+
+#[test]
+fn starward_hk4e() {
+    let hk4e = starward::import_hk4e(starward(), "100000001", 8).unwrap();
+    assert_eq!(ids(&hk4e.list), ["999", "1000"]);
+    assert_eq!(
+        hk4e.lang.map(|lang| lang.to_string()).as_deref(),
+        Some("zh-cn")
+    );
+    assert_eq!(hk4e.list[1].name.as_deref(), Some("胡桃"));
+    assert_eq!(hk4e.list[1].rank_type.as_deref(), Some("5"));
+    assert!(starward::import_hk4e(starward(), "100000009", 8).is_err());
+}
+
+#[test]
+fn starward_hkrpg() {
+    let hkrpg = starward::import_hkrpg(starward(), "600000001", -5).unwrap();
+    assert_eq!(hkrpg.timezone, -5);
+    assert_eq!(ids(&hkrpg.list), ["999", "1000"]);
+    assert_eq!(hkrpg.list[1].gacha_id, "2001");
+}
+
+#[test]
+fn starward_nap() {
+    let nap = starward::import_nap(starward(), "10000001", 8).unwrap();
+    assert_eq!(ids(&nap.list), ["999", "1000"]);
+    assert_eq!(nap.list[0].item_type.as_deref(), Some("邦布"));
+}
@@ -0,0 +1,64 @@
+use uigf::{hkrpg::GachaType, star_rail_station::StarRailStation, GachaAccount};
+
+const STAR_RAIL_STATION: &str = r#"{
+    "uid": "100000001",
+    "pools": {
+        "character": [
+            {"id": "1700000000000000002", "gacha_id": "2001", "item_id": "1208", "time": "2024-01-01 12:00:01"},
+            {"id": "1700000000000000001", "gacha_id": "2001", "item_id": "20000", "time": "2024-01-01 12:00:00"}
+        ],
+        "standard": [
+            {"id": "1700000000000000003", "gacha_id": "1001", "item_id": "20001", "time": "2024-01-01 12:00:02"}
+        ]
+    }
+}"#;
+
+#[test]
+fn to_hkrpg_sorts_by_id_and_resolves_pool_keys() {
+    let station = StarRailStation::from_json(STAR_RAIL_STATION).unwrap();
+    let hkrpg = station.to_hkrpg(8).unwrap();
+
+    assert_eq!(hkrpg.timezone, 8);
+    assert_eq!(
+        hkrpg
+            .list
+            .iter()
+            .map(|item| (item.id.as_str(), item.gacha_type))
+            .collect::<Vec<_>>(),
+        [
+            ("1700000000000000001", GachaType::CharacterEventWarp),
+            ("1700000000000000002", GachaType::CharacterEventWarp),
+            ("1700000000000000003", GachaType::RegularWarp),
+        ]
+    );
+}
+
+#[test]
+fn round_trip_through_hkrpg() {
+    let station = StarRailStation::from_json(STAR_RAIL_STATION).unwrap();
+    let back = StarRailStation::from_hkrpg(&station.to_hkrpg(8).unwrap());
+
+    assert_eq!(back.region_time_zone, Some(8));
+    assert_eq!(
+        back.pools.keys().map(String::as_str).collect::<Vec<_>>(),
+        ["character", "regular"]
+    );
+    assert_eq!(back.pools["character"][0].id, "1700000000000000001");
+}
+
+#[test]
+fn merge_with_existing_account() {
+    let station = StarRailStation::from_json(STAR_RAIL_STATION).unwrap();
+    let mut hkrpg = station.to_hkrpg(8).unwrap();
+    let mut other = hkrpg.clone();
+    other.list.truncate(1);
+    other.list[0].id = "1700000000000000004".into();
+
+    GachaAccount::merge(&mut hkrpg, other).unwrap();
+    assert_eq!(hkrpg.list.len(), 4);
+    assert_eq!(hkrpg.list[3].id, "1700000000000000004");
+
+    let mut mismatch = hkrpg.clone();
+    mismatch.uid = "100000002".parse().unwrap();
+    assert!(GachaAccount::merge(&mut hkrpg, mismatch).is_err());
+}
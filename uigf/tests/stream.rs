@@ -0,0 +1,160 @@
+use uigf::{
+    hk4e::Hk4eItem,
+    stream::{self, Event, Writer},
+    AccountHeader, Game, Item, Uid, UigfV4,
+};
+
+const UIGF: &str = r#"{
+    "info": {"export_timestamp": 1704081600, "export_app": "test", "export_app_version": "1.0", "version": "v4.0"},
+    "hk4e": [
+        {"uid": "100000001", "timezone": 8, "lang": "zh-cn", "list": [
+            {"uigf_gacha_type": "301", "gacha_type": "400", "item_id": "10000046", "time": "2024-01-01 12:00:00", "id": "1704081600000000001"},
+            {"uigf_gacha_type": "200", "gacha_type": "200", "item_id": "15304", "time": "2024-01-01 12:00:01", "id": "1704081601000000002"}
+        ]},
+        {"uid": 100000002, "timezone": 8, "list": []}
+    ],
+    "hkrpg": [{"uid": "600000001", "timezone": -5, "list": [
+        {"gacha_id": "2001", "gacha_type": "11", "item_id": "1208", "time": "2024-01-01 12:00:01", "id": "1704081601000000003"}
+    ]}]
+}"#;
+
+/// 读取后原样写回，返回读到的事件的简要描述与写出的档案
+fn read_and_write(json: &str) -> (Vec<String>, String) {
+    let mut events = Vec::new();
+    let mut writer = None;
+    stream::read(json.as_bytes(), |event| {
+        match event {
+            Event::Info(info) => {
+                events.push("info".to_string());
+                writer = Some(Writer::new(Vec::new(), &info)?);
+            }
+            Event::Account(game, header) => {
+                events.push(format!("{game} {:?}", header.uid));
+                writer.as_mut().unwrap().begin_account(game, header)?;
+            }
+            Event::Item(header, item) => {
+                events.push(format!("{:?} {}", header.uid, item.id()));
+                writer.as_mut().unwrap().write_item(&item)?;
+            }
+        }
+        Ok(())
+    })
+    .unwrap();
+    let output = writer.unwrap().finish().unwrap();
+    (events, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn round_trip() {
+    let (events, output) = read_and_write(UIGF);
+    assert_eq!(
+        events,
+        [
+            "info",
+            r#"hk4e String("100000001")"#,
+            r#"String("100000001") 1704081600000000001"#,
+            r#"String("100000001") 1704081601000000002"#,
+            "hk4e Integer(100000002)",
+            r#"hkrpg String("600000001")"#,
+            r#"String("600000001") 1704081601000000003"#,
+        ]
+    );
+    assert_eq!(
+        UigfV4::from_json(&output).unwrap(),
+        UigfV4::from_json(UIGF).unwrap()
+    );
+}
+
+#[test]
+fn list_before_uid_is_buffered() {
+    let json = r#"{
+        "info": {"export_timestamp": 1704081600, "export_app": "test", "export_app_version": "1.0", "version": "v4.0"},
+        "hk4e": [{"list": [
+            {"uigf_gacha_type": "301", "gacha_type": "301", "item_id": "10000046", "time": "2024-01-01 12:00:00", "id": "1"},
+            {"uigf_gacha_type": "301", "gacha_type": "301", "item_id": "10000046", "time": "2024-01-01 12:00:01", "id": "2"}
+        ], "timezone": 8, "uid": "100000001"}]
+    }"#;
+    let (events, output) = read_and_write(json);
+    assert_eq!(
+        events,
+        [
+            "info",
+            r#"hk4e String("100000001")"#,
+            r#"String("100000001") 1"#,
+            r#"String("100000001") 2"#,
+        ]
+    );
+    assert_eq!(
+        UigfV4::from_json(&output).unwrap(),
+        UigfV4::from_json(json).unwrap()
+    );
+}
+
+#[test]
+fn callback_error_is_returned() {
+    let mut items = 0;
+    let error = stream::read(UIGF.as_bytes(), |event| {
+        if let Event::Item(..) = event {
+            items += 1;
+            return Err("stop".into());
+        }
+        Ok(())
+    })
+    .unwrap_err();
+    assert_eq!(error.to_string(), "stop");
+    assert_eq!(items, 1);
+}
+
+#[test]
+fn unsupported_major_version_is_rejected() {
+    let json = UIGF.replace("v4.0", "v3.0");
+    assert!(stream::read(json.as_bytes(), |_| Ok(())).is_err());
+}
+
+fn header(uid: &str) -> AccountHeader {
+    AccountHeader {
+        uid: Uid::String(uid.into()),
+        timezone: 8,
+        lang: None,
+        region: None,
+    }
+}
+
+#[test]
+fn accounts_of_a_game_must_be_contiguous() {
+    let mut writer = Writer::new(Vec::new(), &UigfV4::new().info).unwrap();
+    writer
+        .begin_account(Game::Hk4e, &header("100000001"))
+        .unwrap();
+    writer
+        .begin_account(Game::Hk4e, &header("100000002"))
+        .unwrap();
+    writer
+        .begin_account(Game::Hkrpg, &header("600000001"))
+        .unwrap();
+    assert!(writer
+        .begin_account(Game::Hk4e, &header("100000003"))
+        .is_err());
+}
+
+#[test]
+fn items_must_match_the_account() {
+    let mut writer = Writer::new(Vec::new(), &UigfV4::new().info).unwrap();
+    let item = Item::from(Hk4eItem {
+        uigf_gacha_type: "301".parse().unwrap(),
+        gacha_type: "301".parse().unwrap(),
+        item_id: "10000046".into(),
+        count: None,
+        time: "2024-01-01 12:00:00".into(),
+        name: None,
+        item_type: None,
+        rank_type: None,
+        id: "1".into(),
+    });
+    assert!(writer.write_item(&item).is_err());
+
+    writer
+        .begin_account(Game::Hkrpg, &header("600000001"))
+        .unwrap();
+    assert!(writer.write_item(&item).is_err());
+}
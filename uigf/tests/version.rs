@@ -0,0 +1,62 @@
+use uigf::{SerializeOptions, UigfV4, UigfVersion};
+
+const UIGF: &str = r#"{
+    "info": {"export_timestamp": 1704081600, "export_app": "test", "export_app_version": "1.0", "version": "v4.0"},
+    "hk4e": [{"uid": "100000001", "timezone": 8, "lang": "zh-cn", "list": [
+        {"uigf_gacha_type": "301", "gacha_type": "400", "item_id": "10000046", "count": "1", "time": "2024-01-01 12:00:00", "name": "胡桃", "item_type": "角色", "rank_type": "5", "id": "1704081600000000001"}
+    ]}],
+    "hkrpg": [{"uid": 600000001, "timezone": -5, "list": [
+        {"gacha_id": "2001", "gacha_type": "11", "item_id": "1208", "time": "2024-01-01 12:00:01", "id": "1704081601000000002"}
+    ]}],
+    "nap": [{"uid": "10000001", "timezone": 8, "list": [
+        {"gacha_id": "5001", "gacha_type": "5", "item_id": "54001", "time": "2024-07-04 12:00:00", "id": "1720065600000000003"}
+    ]}]
+}"#;
+
+#[test]
+fn parse_versions() {
+    assert_eq!("v4.0".parse::<UigfVersion>().unwrap(), UigfVersion::V4_0);
+    assert_eq!("V4".parse::<UigfVersion>().unwrap(), UigfVersion::V4_0);
+    assert_eq!(
+        " 4.2 ".parse::<UigfVersion>().unwrap(),
+        UigfVersion::new(4, 2)
+    );
+    assert!("v4.x".parse::<UigfVersion>().is_err());
+    assert!(UigfVersion::new(3, 0).ensure_supported().is_err());
+}
+
+#[test]
+fn downgrade_round_trips_v4_document() {
+    let uigf = UigfV4::from_json(UIGF).unwrap();
+    let downgraded = uigf.downgrade(UigfVersion::V4_0).unwrap();
+    assert_eq!(downgraded, uigf);
+
+    let json = uigf
+        .to_json_with(&SerializeOptions::new().version(UigfVersion::V4_0))
+        .unwrap();
+    assert_eq!(UigfV4::from_json(&json).unwrap(), uigf);
+}
+
+#[test]
+fn downgrade_sets_target_version() {
+    let mut uigf = UigfV4::from_json(UIGF).unwrap();
+    uigf.info.version = "v4".into();
+    let downgraded = uigf.downgrade(UigfVersion::V4_0).unwrap();
+    assert_eq!(downgraded.info.version, "v4.0");
+    assert_eq!(downgraded.hk4e, uigf.hk4e);
+}
+
+#[test]
+fn downgrade_rejects_unknown_versions() {
+    let uigf = UigfV4::from_json(UIGF).unwrap();
+    assert!(uigf.downgrade(UigfVersion::new(4, 1)).is_err());
+    assert!(uigf.downgrade(UigfVersion::new(5, 0)).is_err());
+    assert!(uigf.downgrade(UigfVersion::new(3, 0)).is_err());
+}
+
+#[test]
+fn newer_minor_versions_are_rejected_on_import() {
+    assert!(UigfVersion::new(4, 1).ensure_supported().is_err());
+    assert!(UigfV4::from_json(&UIGF.replace("v4.0", "v4.1")).is_err());
+    assert!(uigf::stream::read(UIGF.replace("v4.0", "v4.1").as_bytes(), |_| Ok(())).is_err());
+}